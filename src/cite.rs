@@ -0,0 +1,548 @@
+//! Citations of sources and documents.
+//!
+//! A [`Citation`] collects the bibliographic data of either a source or a
+//! page of the site itself. It can be rendered in the formats listed in
+//! [`Format`] for import into reference managers.
+
+use std::fmt::Write;
+use htmlfn::core::AttributeValue;
+use httools::response::{ContentType, Response};
+use raildata::document::{Document, entity, source};
+use raildata::store::DocumentLink;
+use serde_json::{Value, json};
+use crate::{i18n, json};
+use crate::page::snip;
+use crate::page::snip::date::UtcTime;
+use crate::route::Href;
+use crate::state::RequestState;
+
+
+//------------ Format --------------------------------------------------------
+
+/// The formats a citation can be exported in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    BibTex,
+    Ris,
+    CslJson,
+}
+
+impl Format {
+    /// All formats in the order they are offered.
+    pub const ALL: [Format; 3] = [
+        Format::BibTex, Format::Ris, Format::CslJson
+    ];
+
+    /// Returns the format for a path segment.
+    pub fn from_segment(segment: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.segment() == segment)
+    }
+
+    /// Returns the path segment of the format below a document.
+    pub fn segment(self) -> &'static str {
+        match self {
+            Format::BibTex => "cite.bib",
+            Format::Ris => "cite.ris",
+            Format::CslJson => "cite.json",
+        }
+    }
+
+    /// Returns the file name extension of the format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::BibTex => "bib",
+            Format::Ris => "ris",
+            Format::CslJson => "json",
+        }
+    }
+
+    /// Returns the content type of the format.
+    pub fn content_type(self) -> ContentType {
+        match self {
+            Format::BibTex => ContentType::from_static("application/x-bibtex"),
+            Format::Ris => {
+                ContentType::from_static("application/x-research-info-systems")
+            }
+            Format::CslJson => {
+                ContentType::from_static(
+                    "application/vnd.citationstyles.csl+json"
+                )
+            }
+        }
+    }
+
+    /// Returns the human readable name of the format.
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::BibTex => "BibTeX",
+            Format::Ris => "RIS",
+            Format::CslJson => "CSL-JSON",
+        }
+    }
+
+    /// Returns the href of the citation of a document in this format.
+    pub fn href(
+        self, link: impl Into<DocumentLink>, state: &RequestState
+    ) -> impl AttributeValue + '_ {
+        (link.into().href(state), "/", self.segment())
+    }
+}
+
+
+//------------ Kind ----------------------------------------------------------
+
+/// The kind of work being cited.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Kind {
+    Article,
+    Book,
+    Webpage,
+    Dataset,
+    Misc,
+}
+
+impl Kind {
+    fn bibtex(self) -> &'static str {
+        match self {
+            Kind::Article => "article",
+            Kind::Book => "book",
+            Kind::Webpage | Kind::Dataset | Kind::Misc => "misc",
+        }
+    }
+
+    fn ris(self) -> &'static str {
+        match self {
+            Kind::Article => "JOUR",
+            Kind::Book => "BOOK",
+            Kind::Webpage => "ELEC",
+            Kind::Dataset => "DATA",
+            Kind::Misc => "GEN",
+        }
+    }
+
+    fn csl(self) -> &'static str {
+        match self {
+            Kind::Article => "article",
+            Kind::Book => "book",
+            Kind::Webpage => "webpage",
+            Kind::Dataset => "dataset",
+            Kind::Misc => "document",
+        }
+    }
+}
+
+
+//------------ Citation ------------------------------------------------------
+
+/// The bibliographic data of a citable work.
+#[derive(Clone, Debug)]
+pub struct Citation {
+    kind: Kind,
+
+    /// The citation key.
+    id: String,
+
+    title: String,
+    authors: Vec<String>,
+    editors: Vec<String>,
+
+    /// The title of the collection the work is part of.
+    container: Option<String>,
+
+    publisher: Vec<String>,
+
+    /// The issue date as year and optional month and day.
+    date: Option<(i64, Option<i64>, Option<i64>)>,
+
+    volume: Option<String>,
+    edition: Option<String>,
+    pages: Option<String>,
+    isbn: Option<String>,
+    url: Option<String>,
+
+    /// A free-text note.
+    ///
+    /// For pages of the site, this contains the database version.
+    note: Option<String>,
+
+    /// The date the work was accessed for online works.
+    accessed: Option<(i64, i64, i64)>,
+}
+
+impl Citation {
+    /// Creates the citation for a document.
+    ///
+    /// Sources are cited as the work they describe. All other documents
+    /// are cited as a page of the site.
+    pub fn document(doc: Document, state: &RequestState) -> Self {
+        match doc {
+            Document::Source(source) => Self::source(source, state),
+            doc => Self::page(doc, state),
+        }
+    }
+
+    /// Creates the citation for a source.
+    pub fn source(source: source::Document, state: &RequestState) -> Self {
+        let data = source.data();
+        let store = state.store();
+        let name = |link: &entity::Link| {
+            json::text(snip::entity::title(link.document(store), state))
+        };
+        let kind = match data.subtype.into_value() {
+            source::Subtype::Article => Kind::Article,
+            source::Subtype::Book | source::Subtype::Volume => Kind::Book,
+            source::Subtype::Online => Kind::Webpage,
+            _ => Kind::Misc,
+        };
+        Citation {
+            kind,
+            id: source.key().as_str().into(),
+            title: json::text(snip::source::full_title(source, state)),
+            authors: data.author.iter().map(name).collect(),
+            editors: data.editor.iter().map(name).collect(),
+            container: data.collection.as_ref().map(|link| {
+                json::text(snip::source::full_title(
+                    link.document(store), state
+                ))
+            }),
+            publisher: data.publisher.iter().map(name).collect(),
+            date: data.date.as_ref().map(|date| (
+                i64::from(date.year()),
+                date.month().map(i64::from),
+                date.day().map(i64::from),
+            )),
+            volume: data.volume.as_ref().map(|v| v.as_str().into()),
+            edition: data.edition.as_ref().map(|v| v.as_str().into()),
+            pages: data.pages.as_ref().map(|v| v.to_string()),
+            isbn: data.isbn.as_ref().map(|v| v.to_string()),
+            url: data.url.as_ref().map(|v| v.as_str().into()),
+            note: None,
+            accessed: None,
+        }
+    }
+
+    /// Creates the citation for the page of a document on the site.
+    ///
+    /// The citation uses the permalink of the page and notes the version
+    /// of the database the page was rendered from. Since pages change
+    /// whenever the database does, they are considered issued on the day
+    /// they are accessed.
+    pub fn page(doc: Document, state: &RequestState) -> Self {
+        let accessed = UtcTime(std::time::SystemTime::now()).date();
+        let (year, month, day) = accessed;
+        Citation {
+            kind: Kind::Dataset,
+            id: doc.key().as_str().into(),
+            title: json::text(snip::combined::title(doc, state)),
+            authors: Vec::new(),
+            editors: Vec::new(),
+            container: None,
+            publisher: vec![
+                json::text(i18n::term::cite::site(state))
+            ],
+            date: Some((year, Some(month), Some(day))),
+            volume: None,
+            edition: None,
+            pages: None,
+            isbn: None,
+            url: Some(permalink(doc, state)),
+            note: Some(version(state)),
+            accessed: Some(accessed),
+        }
+    }
+
+    /// Returns the response with the citation in the given format.
+    ///
+    /// The citation is offered as a download named after the document key
+    /// with the extension of the format.
+    pub fn response(&self, format: Format, state: &RequestState) -> Response {
+        state.download(
+            format.content_type(),
+            &format!("{}.{}", self.id, format.extension())
+        ).body(self.render(format))
+    }
+
+    /// Returns the content of the citation in the given format.
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::BibTex => self.bibtex(),
+            Format::Ris => self.ris(),
+            Format::CslJson => self.csl_json().to_string(),
+        }
+    }
+
+    /// Renders the citation as a BibTeX entry.
+    pub fn bibtex(&self) -> String {
+        let mut res = String::new();
+        writeln!(
+            res, "@{}{{{},", self.kind.bibtex(), bibtex_key(&self.id)
+        ).expect("writing to string failed");
+        let mut field = |name: &str, value: &str| {
+            writeln!(res, "  {} = {{{}}},", name, bibtex_escape(value))
+                .expect("writing to string failed");
+        };
+        field("title", &self.title);
+        if !self.authors.is_empty() {
+            field("author", &self.authors.join(" and "));
+        }
+        if !self.editors.is_empty() {
+            field("editor", &self.editors.join(" and "));
+        }
+        if let Some(container) = self.container.as_ref() {
+            match self.kind {
+                Kind::Article => field("journal", container),
+                _ => field("series", container),
+            }
+        }
+        if !self.publisher.is_empty() {
+            field("publisher", &self.publisher.join(" and "));
+        }
+        if let Some((year, month, _)) = self.date {
+            field("year", &year.to_string());
+            if let Some(month) = month {
+                field("month", &month.to_string());
+            }
+        }
+        if let Some(volume) = self.volume.as_ref() {
+            field("volume", volume);
+        }
+        if let Some(edition) = self.edition.as_ref() {
+            field("edition", edition);
+        }
+        if let Some(pages) = self.pages.as_ref() {
+            field("pages", pages);
+        }
+        if let Some(isbn) = self.isbn.as_ref() {
+            field("isbn", isbn);
+        }
+        if let Some(url) = self.url.as_ref() {
+            field("url", url);
+        }
+        if let Some(accessed) = self.accessed {
+            field("urldate", &iso_date(accessed));
+        }
+        if let Some(note) = self.note.as_ref() {
+            field("note", note);
+        }
+        res.push_str("}\n");
+        res
+    }
+
+    /// Renders the citation as a RIS record.
+    pub fn ris(&self) -> String {
+        let mut res = String::new();
+        let mut tag = |tag: &str, value: &str| {
+            // RIS is line based, so we can’t have line breaks in values.
+            let value = value.replace(['\r', '\n'], " ");
+            writeln!(res, "{}  - {}", tag, value)
+                .expect("writing to string failed");
+        };
+        tag("TY", self.kind.ris());
+        tag("ID", &self.id);
+        tag("TI", &self.title);
+        self.authors.iter().for_each(|author| tag("AU", author));
+        self.editors.iter().for_each(|editor| tag("ED", editor));
+        if let Some(container) = self.container.as_ref() {
+            tag("T2", container);
+        }
+        self.publisher.iter().for_each(|publisher| tag("PB", publisher));
+        if let Some((year, month, day)) = self.date {
+            tag("PY", &year.to_string());
+            tag("DA", &format!("{:04}/{}/{}",
+                year,
+                month.map(|m| format!("{:02}", m)).unwrap_or_default(),
+                day.map(|d| format!("{:02}", d)).unwrap_or_default(),
+            ));
+        }
+        if let Some(volume) = self.volume.as_ref() {
+            tag("VL", volume);
+        }
+        if let Some(edition) = self.edition.as_ref() {
+            tag("ET", edition);
+        }
+        if let Some(pages) = self.pages.as_ref() {
+            tag("SP", pages);
+        }
+        if let Some(isbn) = self.isbn.as_ref() {
+            tag("SN", isbn);
+        }
+        if let Some(url) = self.url.as_ref() {
+            tag("UR", url);
+        }
+        if let Some(accessed) = self.accessed {
+            tag("Y2", &iso_date(accessed));
+        }
+        if let Some(note) = self.note.as_ref() {
+            tag("N1", note);
+        }
+        tag("ER", "");
+        res
+    }
+
+    /// Renders the citation as CSL-JSON.
+    ///
+    /// CSL-JSON is always a list of items, so this is a list with one
+    /// element.
+    pub fn csl_json(&self) -> Value {
+        let names = |names: &[String]| -> Vec<Value> {
+            names.iter().map(|name| json!({ "literal": name })).collect()
+        };
+        let mut item = json!({
+            "id": self.id,
+            "type": self.kind.csl(),
+            "title": self.title,
+        });
+        let map = item.as_object_mut().expect("not an object");
+        if !self.authors.is_empty() {
+            map.insert("author".into(), names(&self.authors).into());
+        }
+        if !self.editors.is_empty() {
+            map.insert("editor".into(), names(&self.editors).into());
+        }
+        if let Some(container) = self.container.as_ref() {
+            map.insert(
+                "container-title".into(), container.as_str().into()
+            );
+        }
+        if !self.publisher.is_empty() {
+            map.insert(
+                "publisher".into(), self.publisher.join("; ").into()
+            );
+        }
+        if let Some((year, month, day)) = self.date {
+            let parts: Vec<_> = Some(year).into_iter()
+                .chain(month).chain(month.and(day))
+                .collect();
+            map.insert("issued".into(), json!({ "date-parts": [parts] }));
+        }
+        if let Some(volume) = self.volume.as_ref() {
+            map.insert("volume".into(), volume.as_str().into());
+        }
+        if let Some(edition) = self.edition.as_ref() {
+            map.insert("edition".into(), edition.as_str().into());
+        }
+        if let Some(pages) = self.pages.as_ref() {
+            map.insert("page".into(), pages.as_str().into());
+        }
+        if let Some(isbn) = self.isbn.as_ref() {
+            map.insert("ISBN".into(), isbn.as_str().into());
+        }
+        if let Some(url) = self.url.as_ref() {
+            map.insert("URL".into(), url.as_str().into());
+        }
+        if let Some((year, month, day)) = self.accessed {
+            map.insert(
+                "accessed".into(),
+                json!({ "date-parts": [[year, month, day]] })
+            );
+        }
+        if let Some(note) = self.note.as_ref() {
+            map.insert("note".into(), note.as_str().into());
+        }
+        Value::Array(vec![item])
+    }
+}
+
+
+//------------ permalink and version -----------------------------------------
+
+/// Returns the permanent URL of a document.
+///
+/// This is always the absolute URL based on the configured base URL of
+/// the site and without a language, even if the page was rendered for a
+/// static export.
+pub fn permalink(doc: Document, state: &RequestState) -> String {
+    format!(
+        "{}{}/{}",
+        state.server().url_base(),
+        crate::route::document::SEGMENT,
+        doc.key().as_str()
+    )
+}
+
+/// Returns a description of the database version used for rendering.
+///
+/// If the database is kept in git, this is the commit ID. Otherwise, we
+/// fall back to the time the database was loaded.
+pub fn version(state: &RequestState) -> String {
+    let database = state.database();
+    match database.commit() {
+        Some(commit) => format!(
+            "{} {}", json::text(i18n::term::cite::version(state)), commit
+        ),
+        None => format!(
+            "{} {}", json::text(i18n::term::cite::loaded(state)),
+            UtcTime(database.loaded())
+        ),
+    }
+}
+
+
+//------------ Helpers -------------------------------------------------------
+
+/// Formats a date as an ISO 8601 date.
+fn iso_date((year, month, day): (i64, i64, i64)) -> String {
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Turns a document key into a valid BibTeX key.
+///
+/// Keys may contain characters BibTeX doesn’t allow, so we replace
+/// everything but ASCII alphanumerics and a few safe characters.
+fn bibtex_key(key: &str) -> String {
+    key.chars().map(|ch| {
+        let safe = matches!(ch, '-' | '_' | ':' | '.');
+        if ch.is_ascii_alphanumeric() || safe {
+            ch
+        }
+        else {
+            '_'
+        }
+    }).collect()
+}
+
+/// Escapes the characters with special meaning in BibTeX values.
+///
+/// Most of these can simply be prefixed with a backslash. The backslash
+/// itself and the characters that would become accents need a command.
+fn bibtex_escape(value: &str) -> String {
+    let mut res = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '{' | '}' | '%' | '&' | '$' | '#' | '_' => {
+                res.push('\\');
+                res.push(ch);
+            }
+            '\\' => res.push_str("\\textbackslash{}"),
+            '~' => res.push_str("\\textasciitilde{}"),
+            '^' => res.push_str("\\textasciicircum{}"),
+            _ => res.push(ch)
+        }
+    }
+    res
+}
+
+
+//------------ Tests ---------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn escape_bibtex() {
+        assert_eq!(bibtex_escape("Köln–Minden"), "Köln–Minden");
+        assert_eq!(bibtex_escape("50% & {more}"), "50\\% \\& \\{more\\}");
+        assert_eq!(bibtex_escape("a\\b"), "a\\textbackslash{}b");
+        assert_eq!(
+            bibtex_escape("~^_#$"),
+            "\\textasciitilde{}\\textasciicircum{}\\_\\#\\$"
+        );
+    }
+
+    #[test]
+    fn key_bibtex() {
+        assert_eq!(bibtex_key("source.de.db-1/2"), "source.de.db-1_2");
+        assert_eq!(bibtex_key("line.de.1700 neu"), "line.de.1700_neu");
+    }
+}
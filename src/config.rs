@@ -39,9 +39,26 @@ pub struct Config {
 
     /// The base URL of the site.
     pub url_base: String,
+
+    /// Seconds between checks of the database for changes.
+    ///
+    /// If this is zero, the database is never reloaded.
+    #[serde(default = "Config::default_reload_interval")]
+    pub reload_interval: u64,
+
+    /// Whether to serve the administrative pages.
+    ///
+    /// These pages expose internals such as file system paths, so they
+    /// are disabled by default.
+    #[serde(default)]
+    pub admin: bool,
 }
 
 impl Config {
+    fn default_reload_interval() -> u64 {
+        10
+    }
+
     pub fn config_args(app: Command) -> Command {
         ConfigArgs::augment_args(app)
     }
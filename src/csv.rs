@@ -0,0 +1,112 @@
+//! Building CSV files.
+//!
+//! We only offer a few simple tables for download, so the files are built
+//! directly as strings the same way as our SVG images.
+
+use std::fmt::{self, Write};
+use httools::response::{ContentType, Response};
+use crate::state::RequestState;
+
+
+//------------ Csv -----------------------------------------------------------
+
+/// The media type of CSV documents.
+const CONTENT_TYPE: ContentType = ContentType::from_static(
+    "text/csv; charset=utf-8"
+);
+
+/// A CSV document under construction.
+pub struct Csv {
+    content: String,
+}
+
+impl Csv {
+    /// Creates a new document with the given column names.
+    pub fn new(header: &[&str]) -> Self {
+        let mut res = Csv { content: String::new() };
+        res.row(header);
+        res
+    }
+
+    /// Adds a row with the given fields.
+    ///
+    /// Fields are quoted if necessary.
+    pub fn row<T: fmt::Display>(
+        &mut self, fields: impl IntoIterator<Item = T>
+    ) {
+        let mut field = String::new();
+        for (idx, item) in fields.into_iter().enumerate() {
+            if idx > 0 {
+                self.content.push(',');
+            }
+            field.clear();
+            let _ = write!(field, "{}", item);
+            escape(&field, &mut self.content);
+        }
+        self.content.push_str("\r\n");
+    }
+
+    /// Finishes the document and returns its content.
+    pub fn finish(self) -> String {
+        self.content
+    }
+
+    /// Finishes the document and returns it as a download response.
+    pub fn response(self, filename: &str, state: &RequestState) -> Response {
+        state.download(CONTENT_TYPE, filename).body(self.finish())
+    }
+}
+
+
+//------------ escape --------------------------------------------------------
+
+/// Appends `field` to `target` quoting it if necessary.
+fn escape(field: &str, target: &mut String) {
+    if !field.contains([',', '"', '\r', '\n']) {
+        target.push_str(field);
+        return
+    }
+    target.push('"');
+    for ch in field.chars() {
+        if ch == '"' {
+            target.push('"');
+        }
+        target.push(ch);
+    }
+    target.push('"');
+}
+
+
+//------------ Tests ---------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn escaped(field: &str) -> String {
+        let mut res = String::new();
+        escape(field, &mut res);
+        res
+    }
+
+    #[test]
+    fn escape_fields() {
+        assert_eq!(escaped("plain"), "plain");
+        assert_eq!(escaped(""), "");
+        assert_eq!(escaped("a,b"), "\"a,b\"");
+        assert_eq!(escaped("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escaped("two\nlines"), "\"two\nlines\"");
+        assert_eq!(escaped("cr\r"), "\"cr\r\"");
+    }
+
+    #[test]
+    fn rows() {
+        let mut csv = Csv::new(&["year", "name"]);
+        csv.row(["1900", "Bahn, Neue"]);
+        csv.row([1901, 2]);
+        assert_eq!(
+            csv.finish(),
+            "year,name\r\n1900,\"Bahn, Neue\"\r\n1901,2\r\n"
+        );
+    }
+}
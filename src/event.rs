@@ -0,0 +1,65 @@
+//! Helpers for the events of documents.
+//!
+//! Lines, points, entities, and structures all describe their history as
+//! a list of events. The data doesn’t keep these in chronological order,
+//! so everything looking at them in order should go through
+//! [`sorted_events`].
+
+use raildata::document::{entity, line, point, source, structure};
+use raildata::types::date::EventDate;
+
+
+//------------ sorted_events -------------------------------------------------
+
+/// Returns events sorted by their date.
+///
+/// Events are ordered by their earliest date with partial dates going
+/// before full dates within the same year or month. Events without a date
+/// go first. Otherwise the order of the events is kept.
+pub fn sorted_events<'a, T: 'a>(
+    events: impl IntoIterator<Item = &'a T>,
+    date: impl Fn(&T) -> &EventDate,
+) -> Vec<&'a T> {
+    let mut res: Vec<_> = events.into_iter().collect();
+    res.sort_by_key(|event| sort_key(date(event)));
+    res
+}
+
+/// Returns the key for sorting by an event date.
+///
+/// This is the order used by [`sorted_events`].
+pub fn sort_key(date: &EventDate) -> impl Ord {
+    date.iter().map(|date| {
+        (date.year(), date.month().unwrap_or(0), date.day().unwrap_or(0))
+    }).min()
+}
+
+
+//------------ Cited ---------------------------------------------------------
+
+/// An event of a document citing sources.
+pub trait Cited {
+    /// Returns the date of the event.
+    fn date(&self) -> &EventDate;
+
+    /// Returns the sources cited by the event.
+    fn sources(&self) -> impl Iterator<Item = &source::Link>;
+}
+
+macro_rules! cited {
+    ( $( $event:ty ),* ) => {
+        $(
+            impl Cited for $event {
+                fn date(&self) -> &EventDate {
+                    &self.date
+                }
+
+                fn sources(&self) -> impl Iterator<Item = &source::Link> {
+                    self.document.iter().chain(self.source.iter())
+                }
+            }
+        )*
+    }
+}
+
+cited!(entity::Event, line::Event, point::Event, structure::Event);
@@ -0,0 +1,343 @@
+//! Exporting the site as static HTML files.
+//!
+//! The export contains one directory per language. Each page is written
+//! as an `index.html` into a directory tree mirroring the URL of the page,
+//! so that the export can be served by any static web server. All links
+//! are relative, so the export can be placed anywhere.
+//!
+//! Links to pages point to their directory rather than the `index.html`
+//! inside it. The export therefore needs to be served by a web server that
+//! answers requests for a directory with its `index.html`. Opening the
+//! files directly via `file://` URLs will leave these links broken.
+
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use raildata::document::{Document, entity};
+use raildata::load::report::Failed;
+use crate::{json, page, route};
+use crate::cite::{Citation, Format};
+use crate::lang::Lang;
+use crate::page::Page;
+use crate::state::{RequestState, ServerState};
+
+
+//------------ export --------------------------------------------------------
+
+/// Exports the site into the directory `target`.
+pub fn export(server: Arc<ServerState>, target: &Path) -> Result<(), Failed> {
+    let res = Exporter { server, target }.export();
+    if let Err(err) = res {
+        eprintln!("Export to {} failed: {}", target.display(), err);
+        return Err(Failed)
+    }
+    Ok(())
+}
+
+
+//------------ Exporter ------------------------------------------------------
+
+struct Exporter<'a> {
+    server: Arc<ServerState>,
+    target: &'a Path,
+}
+
+impl Exporter<'_> {
+    fn export(&self) -> Result<(), io::Error> {
+        fs::create_dir_all(self.target)?;
+        fs::write(
+            self.target.join("index.html"),
+            format!(
+                "<!DOCTYPE html>\n\
+                 <meta http-equiv=\"refresh\" content=\"0; url={0}/\">\n\
+                 <a href=\"{0}/\">{0}</a>\n",
+                Lang::default().code()
+            )
+        )?;
+        for lang in Lang::all() {
+            self.export_lang(lang)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the state for rendering the page at `path`.
+    ///
+    /// Each page needs its own state since URLs are relative to the page.
+    fn state(&self, lang: Lang, path: &[&str]) -> RequestState {
+        RequestState::detached(self.server.clone(), lang, path)
+    }
+
+    /// Exports all pages in the given language.
+    fn export_lang(&self, lang: Lang) -> Result<(), io::Error> {
+        let base = self.target.join(lang.code());
+
+        // Static assets.
+        let assets = base.join(route::assets::SEGMENT);
+        for (path, content) in route::assets::all() {
+            let path = assets.join(path);
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(path, content)?;
+        }
+
+        // Auxiliary pages.
+        let root = self.state(lang, &[]);
+        write_page(&base, &[], page::home::standard(&root))?;
+        let path = [route::aux::Countries::SEGMENT];
+        write_page(
+            &base, &path, page::aux::countries(&self.state(lang, &path))
+        )?;
+
+        // Documents.
+        let store = root.store();
+        let json_state = self.state(lang, &[route::document::SEGMENT]);
+        for link in store.links() {
+            let doc = link.document(store);
+            let path = [route::document::SEGMENT, doc.key().as_str()];
+            self.export_document(doc, lang, &base, &path)?;
+            self.export_json(doc, &base, &json_state)?;
+        }
+        Ok(())
+    }
+
+    /// Exports all pages of a document.
+    fn export_document(
+        &self,
+        doc: Document,
+        lang: Lang,
+        base: &Path,
+        path: &[&str],
+    ) -> Result<(), io::Error> {
+        let page_state = self.state(lang, path);
+        let state = &page_state;
+        let sub = |segment: &'static str| [path, &[segment]].concat();
+        match doc {
+            Document::Line(line) => {
+                write_citations(doc, base, path, state)?;
+                write_page(base, path, page::line::overview(line, state))?;
+                let path = sub(route::line::Route::SEGMENT);
+                write_page(
+                    base, &path,
+                    page::line::route(line, &self.state(lang, &path))
+                )?;
+                let path = sub(route::line::DetailedRoute::SEGMENT);
+                write_page(
+                    base, &path,
+                    page::line::detailed_route(line, &self.state(lang, &path))
+                )?;
+                let path = sub(route::line::History::SEGMENT);
+                write_page(
+                    base, &path,
+                    page::line::history(line, &self.state(lang, &path))
+                )?;
+                let path = sub(route::line::Sources::SEGMENT);
+                write_page(
+                    base, &path,
+                    page::line::sources(line, &self.state(lang, &path))
+                )?;
+                let path = sub(route::line::Map::SEGMENT);
+                write_page(
+                    base, &path,
+                    page::line::map(line, &self.state(lang, &path))
+                )?;
+                write_file(
+                    base, &sub(route::line::MapSvg::SEGMENT),
+                    page::line::map::svg(line, state).finish().as_bytes()
+                )?;
+                write_file(
+                    base, &sub(route::line::DiagramSvg::SEGMENT),
+                    page::line::diagram::svg(line, state).finish().as_bytes()
+                )
+            }
+            Document::Entity(entity) => {
+                match entity.data().subtype.into_value() {
+                    entity::Subtype::Country | entity::Subtype::Region => {
+                        write_citations(doc, base, path, state)?;
+                        write_page(
+                            base, path, page::region::overview(entity, state)
+                        )?;
+                        let path = sub(route::region::Lines::SEGMENT);
+                        write_page(
+                            base, &path,
+                            page::region::lines(
+                                entity, &self.state(lang, &path)
+                            )
+                        )?;
+                        let path = sub(route::region::Points::SEGMENT);
+                        write_page(
+                            base, &path,
+                            page::region::points(
+                                entity, &self.state(lang, &path)
+                            )
+                        )?;
+                        let path = sub(route::region::Statistics::SEGMENT);
+                        write_page(
+                            base, &path,
+                            page::region::statistics(
+                                entity, &self.state(lang, &path)
+                            )
+                        )?;
+                        write_file(
+                            base, &sub(route::region::StatisticsSvg::SEGMENT),
+                            page::region::statistics::svg(
+                                entity, state
+                            ).finish().as_bytes()
+                        )?;
+                        write_file(
+                            base, &sub(route::region::StatisticsCsv::SEGMENT),
+                            page::region::statistics::csv(
+                                entity, state
+                            ).finish().as_bytes()
+                        )
+                    }
+                    _ => {
+                        write_citations(doc, base, path, state)?;
+                        write_page(
+                            base, path, page::entity::overview(entity, state)
+                        )?;
+                        let path = sub(route::entity::Lines::SEGMENT);
+                        write_page(
+                            base, &path,
+                            page::entity::lines(
+                                entity, &self.state(lang, &path)
+                            )
+                        )?;
+                        let path = sub(route::entity::Points::SEGMENT);
+                        write_page(
+                            base, &path,
+                            page::entity::points(
+                                entity, &self.state(lang, &path)
+                            )
+                        )?;
+                        let path = sub(route::entity::Lineage::SEGMENT);
+                        write_page(
+                            base, &path,
+                            page::entity::lineage(
+                                entity, &self.state(lang, &path)
+                            )
+                        )?;
+                        write_file(
+                            base, &sub(route::entity::LineageSvg::SEGMENT),
+                            page::entity::lineage::svg(
+                                entity, state
+                            ).finish().as_bytes()
+                        )
+                    }
+                }
+            }
+            Document::Point(point) => {
+                write_citations(doc, base, path, state)?;
+                write_page(base, path, page::point::overview(point, state))?;
+                let path = sub(route::point::History::SEGMENT);
+                write_page(
+                    base, &path,
+                    page::point::history(point, &self.state(lang, &path))
+                )
+            }
+            Document::Source(source) => {
+                write_citations(doc, base, path, state)?;
+                write_page(base, path, page::source::overview(source, state))
+            }
+            Document::Structure(structure) => {
+                write_citations(doc, base, path, state)?;
+                write_page(
+                    base, path, page::structure::overview(structure, state)
+                )
+            }
+            Document::Path(doc_path) => {
+                write_page(base, path, page::path::overview(doc_path, state))?;
+                write_file(
+                    base, &sub(route::path::OutlineSvg::SEGMENT),
+                    page::path::svg(doc_path, state).finish().as_bytes()
+                )?;
+                write_file(
+                    base, &sub(route::path::Gpx::SEGMENT),
+                    page::path::gpx(doc_path, state).finish().as_bytes()
+                )
+            }
+            _ => Ok(())
+        }
+    }
+
+    /// Exports the JSON and GeoJSON representations of a document.
+    ///
+    /// These are files next to the directory of the document, so `state`
+    /// needs to be the state for the document directory.
+    fn export_json(
+        &self, doc: Document, base: &Path, state: &RequestState
+    ) -> Result<(), io::Error> {
+        use crate::route::document::Format;
+
+        let key = doc.key().as_str();
+        write_file(
+            base,
+            &[
+                route::document::SEGMENT,
+                &[key, Format::Json.suffix()].concat()
+            ],
+            &json::to_vec(&json::document::document(doc, state))
+        )?;
+        if let Some(value) = json::geojson::document(doc, state) {
+            write_file(
+                base,
+                &[
+                    route::document::SEGMENT,
+                    &[key, Format::GeoJson.suffix()].concat()
+                ],
+                &json::to_vec(&value)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+
+//------------ write_page ----------------------------------------------------
+
+/// Writes a page to the directory given by `path` below `base`.
+fn write_page(
+    base: &Path, path: &[&str], page: impl Page
+) -> Result<(), io::Error> {
+    let dir = path.iter().fold(PathBuf::from(base), |dir, item| {
+        dir.join(item)
+    });
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join("index.html"), page.render_bytes())
+}
+
+
+//------------ write_citations -----------------------------------------------
+
+/// Writes the citations of a document in all formats.
+fn write_citations(
+    doc: Document, base: &Path, path: &[&str], state: &RequestState
+) -> Result<(), io::Error> {
+    let citation = Citation::document(doc, state);
+    for format in Format::ALL {
+        write_file(
+            base,
+            &[path, &[format.segment()]].concat(),
+            citation.render(format).as_bytes()
+        )?;
+    }
+    Ok(())
+}
+
+
+//------------ write_file ----------------------------------------------------
+
+/// Writes a file to `path` below `base`.
+///
+/// The last element of `path` is the file name.
+fn write_file(
+    base: &Path, path: &[&str], content: &[u8]
+) -> Result<(), io::Error> {
+    let path = path.iter().fold(PathBuf::from(base), |dir, item| {
+        dir.join(item)
+    });
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, content)
+}
@@ -0,0 +1,162 @@
+//! Geometry of documents.
+//!
+//! The geometry of our data lives in path documents. Points refer to named
+//! nodes on paths via their site and lines refer to sections of paths via
+//! their course. This module collects the functions to resolve these
+//! references into actual coordinates.
+
+use raildata::document::{line, path, point};
+use raildata::store::FullStore;
+
+
+//------------ Coord ---------------------------------------------------------
+
+/// A coordinate as longitude and latitude in WGS 84.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Coord {
+    pub lon: f64,
+    pub lat: f64,
+}
+
+impl Coord {
+    /// Returns the distance to another coordinate in metres.
+    ///
+    /// This uses the haversine formula which is good enough for our
+    /// purposes.
+    pub fn distance(self, other: Coord) -> f64 {
+        const EARTH_RADIUS: f64 = 6_371_008.8;
+
+        let lat1 = self.lat.to_radians();
+        let lat2 = other.lat.to_radians();
+        let dlat = lat2 - lat1;
+        let dlon = (other.lon - self.lon).to_radians();
+        let a = (dlat / 2.).sin().powi(2)
+            + lat1.cos() * lat2.cos() * (dlon / 2.).sin().powi(2);
+        2. * EARTH_RADIUS * a.sqrt().asin()
+    }
+}
+
+
+//------------ path ----------------------------------------------------------
+
+/// Returns all nodes of a path as coordinates.
+pub fn path_coords(path: path::Document) -> Vec<Coord> {
+    path.data().nodes().iter().map(|node| {
+        Coord { lon: node.lon(), lat: node.lat() }
+    }).collect()
+}
+
+/// Returns the coordinates of a path between two named nodes.
+///
+/// If the end node comes before the start node, the coordinates are
+/// returned in reverse order. Returns `None` if either node does not exist.
+pub fn path_section(
+    path: path::Document, start: &str, end: &str
+) -> Option<Vec<Coord>> {
+    let start = path.data().node_index(start)?;
+    let end = path.data().node_index(end)?;
+    let nodes = path_coords(path);
+    if start <= end {
+        Some(nodes[start..=end].to_vec())
+    }
+    else {
+        Some(nodes[end..=start].iter().rev().copied().collect())
+    }
+}
+
+/// Returns the length of a sequence of coordinates in metres.
+pub fn length(coords: &[Coord]) -> f64 {
+    coords.windows(2).map(|pair| pair[0].distance(pair[1])).sum()
+}
+
+
+//------------ line ----------------------------------------------------------
+
+/// Returns the course of a line as a list of coordinate sequences.
+///
+/// There is one sequence for each segment of the line’s course. Segments
+/// that refer to unknown nodes are skipped.
+pub fn line_course(
+    line: line::Document, store: &FullStore
+) -> Vec<Vec<Coord>> {
+    line.data().course.iter().filter_map(|segment| {
+        path_section(
+            segment.path.document(store),
+            segment.start.as_str(), segment.end.as_str(),
+        )
+    }).collect()
+}
+
+
+//------------ point ---------------------------------------------------------
+
+/// Returns the location of a point.
+///
+/// This is the first node of the point’s site that can be resolved.
+pub fn point_coord(
+    point: point::Document, store: &FullStore
+) -> Option<Coord> {
+    point.data().site.iter().find_map(|(path, node)| {
+        let path = path.document(store);
+        let index = path.data().node_index(node.as_str())?;
+        path.data().nodes().get(index).map(|node| {
+            Coord { lon: node.lon(), lat: node.lat() }
+        })
+    })
+}
+
+
+//------------ Projection ----------------------------------------------------
+
+/// A projection of coordinates onto a drawing area.
+///
+/// This is a simple equirectangular projection centred on the area of
+/// interest which is good enough for drawing individual lines or paths.
+#[derive(Clone, Copy, Debug)]
+pub struct Projection {
+    min_lon: f64,
+    max_lat: f64,
+    scale: f64,
+    lon_factor: f64,
+    margin: f64,
+}
+
+impl Projection {
+    /// Creates a projection fitting all coordinates into the given area.
+    ///
+    /// The area has the given `width` and `height` with `margin` left
+    /// empty on all sides. Returns `None` if there are no coordinates.
+    pub fn fit<'a>(
+        coords: impl IntoIterator<Item = &'a Coord>,
+        width: f64, height: f64, margin: f64,
+    ) -> Option<Self> {
+        let mut coords = coords.into_iter();
+        let first = coords.next()?;
+        let (mut min_lon, mut max_lon) = (first.lon, first.lon);
+        let (mut min_lat, mut max_lat) = (first.lat, first.lat);
+        for coord in coords {
+            min_lon = min_lon.min(coord.lon);
+            max_lon = max_lon.max(coord.lon);
+            min_lat = min_lat.min(coord.lat);
+            max_lat = max_lat.max(coord.lat);
+        }
+        let lon_factor = ((min_lat + max_lat) / 2.).to_radians().cos();
+        let dx = ((max_lon - min_lon) * lon_factor).max(f64::EPSILON);
+        let dy = (max_lat - min_lat).max(f64::EPSILON);
+        let scale = (
+            (width - 2. * margin) / dx
+        ).min(
+            (height - 2. * margin) / dy
+        );
+        Some(Projection { min_lon, max_lat, scale, lon_factor, margin })
+    }
+
+    /// Projects a coordinate onto the drawing area.
+    pub fn project(&self, coord: Coord) -> (f64, f64) {
+        (
+            self.margin
+                + (coord.lon - self.min_lon) * self.lon_factor * self.scale,
+            self.margin + (self.max_lat - coord.lat) * self.scale,
+        )
+    }
+}
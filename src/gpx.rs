@@ -0,0 +1,65 @@
+//! Building GPX files.
+//!
+//! GPX is the XML format most GPS devices and mapping tools understand.
+//! We only produce tracks from sequences of coordinates, so the files are
+//! built directly as strings the same way as our SVG images.
+
+use std::fmt::Write;
+use httools::response::{ContentType, Response};
+use crate::geo::Coord;
+use crate::state::RequestState;
+use crate::svg::escape;
+
+
+//------------ Gpx -----------------------------------------------------------
+
+/// The media type of GPX documents.
+const CONTENT_TYPE: ContentType = ContentType::from_static(
+    "application/gpx+xml"
+);
+
+/// A GPX document under construction.
+pub struct Gpx {
+    content: String,
+}
+
+impl Gpx {
+    /// Creates a new document with the given name.
+    pub fn new(name: &str) -> Self {
+        let mut content = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+             <gpx version=\"1.1\" creator=\"railsite\" \
+             xmlns=\"http://www.topografix.com/GPX/1/1\">\
+             <metadata><name>"
+        );
+        escape(name, &mut content);
+        content.push_str("</name></metadata>");
+        Gpx { content }
+    }
+
+    /// Adds a track with a single segment through the given coordinates.
+    pub fn track(
+        &mut self, name: &str, coords: impl IntoIterator<Item = Coord>
+    ) {
+        self.content.push_str("<trk><name>");
+        escape(name, &mut self.content);
+        self.content.push_str("</name><trkseg>");
+        for coord in coords {
+            let _ = write!(self.content,
+                "<trkpt lat=\"{:.7}\" lon=\"{:.7}\"/>", coord.lat, coord.lon
+            );
+        }
+        self.content.push_str("</trkseg></trk>");
+    }
+
+    /// Finishes the document and returns its content.
+    pub fn finish(mut self) -> String {
+        self.content.push_str("</gpx>\n");
+        self.content
+    }
+
+    /// Finishes the document and returns it as a download response.
+    pub fn response(self, filename: &str, state: &RequestState) -> Response {
+        state.download(CONTENT_TYPE, filename).body(self.finish())
+    }
+}
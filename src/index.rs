@@ -0,0 +1,467 @@
+//! Cross references not kept by the store.
+//!
+//! Some pages need to know which documents refer to a given document in
+//! ways the store doesn’t keep cross references for. Finding these by
+//! going over all documents for every request is too slow, so we collect
+//! them once when the database is loaded.
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use raildata::document::{
+    Document, entity, line, path, point, source, structure
+};
+use raildata::store::{DocumentLink, FullStore};
+use crate::event;
+use crate::event::Cited;
+
+
+//------------ Index ---------------------------------------------------------
+
+/// The cross references of a database.
+#[derive(Default)]
+pub struct Index {
+    /// The documents citing a source.
+    citations: HashMap<source::Link, Citations>,
+
+    /// The structures belonging to a line or point.
+    structures: HashMap<DocumentLink, Vec<structure::Link>>,
+
+    /// The lines using a path for their course.
+    path_lines: HashMap<path::Link, Vec<line::Link>>,
+
+    /// The points located on a path with the index of their node.
+    path_points: HashMap<path::Link, Vec<(usize, point::Link)>>,
+
+    /// The lines ever owned or operated by an entity.
+    holdings: HashMap<entity::Link, Vec<Holding>>,
+
+    /// The entities naming an entity as their successor.
+    predecessors: HashMap<entity::Link, Vec<entity::Link>>,
+
+    /// The entities naming an entity as one of their superiors.
+    subsidiaries: HashMap<entity::Link, Vec<entity::Link>>,
+
+    /// The parent regions of a country or region.
+    region_parents: HashMap<entity::Link, Vec<entity::Link>>,
+
+    /// The child regions of a country or region.
+    region_children: HashMap<entity::Link, Vec<entity::Link>>,
+
+    /// The countries and regions at the top of the region tree.
+    ///
+    /// These are the regions without a parent region plus one region of
+    /// each cycle of parents.
+    region_roots: Vec<entity::Link>,
+}
+
+impl Index {
+    /// Collects the cross references of all documents in the store.
+    pub fn generate(store: &FullStore) -> Self {
+        let mut res = Index::default();
+        let mut regions = Vec::new();
+        for link in store.links() {
+            match link.document(store) {
+                Document::Line(line) => {
+                    for source in cited(line.data().events.iter()) {
+                        res.citations.entry(source).or_default().lines.push(
+                            line.link()
+                        );
+                    }
+                    res.add_holdings(line);
+                    let mut seen = HashSet::new();
+                    for segment in line.data().course.iter() {
+                        if seen.insert(segment.path) {
+                            res.path_lines.entry(
+                                segment.path
+                            ).or_default().push(line.link());
+                        }
+                    }
+                }
+                Document::Point(point) => {
+                    for source in cited(point.data().events.iter()) {
+                        res.citations.entry(source).or_default().points.push(
+                            point.link()
+                        );
+                    }
+                    let mut seen = HashSet::new();
+                    for (site, node) in point.data().site.iter() {
+                        if !seen.insert(*site) {
+                            continue
+                        }
+                        let idx = site.document(store).data().node_index(
+                            node.as_str()
+                        );
+                        if let Some(idx) = idx {
+                            res.path_points.entry(*site).or_default().push(
+                                (idx, point.link())
+                            );
+                        }
+                    }
+                }
+                Document::Entity(entity) => {
+                    for source in cited(entity.data().events.iter()) {
+                        res.citations.entry(
+                            source
+                        ).or_default().entities.push(entity.link());
+                    }
+                    res.add_relations(entity, store);
+                    if is_region(entity) {
+                        res.add_region(entity, store);
+                        regions.push(entity.link());
+                    }
+                }
+                Document::Source(source) => {
+                    if let Some(collection) = source.data().collection {
+                        res.citations.entry(
+                            collection
+                        ).or_default().members.push(source.link());
+                    }
+                }
+                Document::Structure(structure) => {
+                    let mut seen = HashSet::new();
+                    for event in structure.data().events.iter() {
+                        let links = event.lines.iter().map(|link| {
+                            DocumentLink::from(*link)
+                        }).chain(event.points.iter().map(|link| {
+                            DocumentLink::from(*link)
+                        }));
+                        for link in links {
+                            if seen.insert(link) {
+                                res.structures.entry(link).or_default().push(
+                                    structure.link()
+                                );
+                            }
+                        }
+                    }
+                }
+                _ => { }
+            }
+        }
+        res.region_roots = roots(&regions, &res.region_parents);
+        res
+    }
+
+    /// Adds the owners and operators of a line to the holdings.
+    fn add_holdings(&mut self, line: line::Document) {
+        let mut found: Vec<(entity::Link, Holding)> = Vec::new();
+        for event in line.data().events.iter() {
+            let owners = event.owner.iter().flatten().flat_map(|list| {
+                list.iter().map(|link| (*link, true))
+            });
+            let operators = event.operator.iter().flatten().flat_map(|list| {
+                list.iter().map(|link| (*link, false))
+            });
+            for (entity, owner) in owners.chain(operators) {
+                let idx = match found.iter().position(|item| {
+                    item.0 == entity
+                }) {
+                    Some(idx) => idx,
+                    None => {
+                        found.push((entity, Holding {
+                            line: line.link(), owner: false, operator: false
+                        }));
+                        found.len() - 1
+                    }
+                };
+                if owner {
+                    found[idx].1.owner = true
+                }
+                else {
+                    found[idx].1.operator = true
+                }
+            }
+        }
+        for (entity, holding) in found {
+            self.holdings.entry(entity).or_default().push(holding)
+        }
+    }
+
+    /// Adds the successors and superiors of an entity to the relations.
+    fn add_relations(&mut self, entity: entity::Document, store: &FullStore) {
+        let mut successors = HashSet::new();
+        let mut superiors = HashSet::new();
+        for event in entity.data().events.iter() {
+            if let Some(successor) = event.successor.as_ref() {
+                let successor = successor.document(store).link();
+                if successors.insert(successor) {
+                    self.predecessors.entry(successor).or_default().push(
+                        entity.link()
+                    );
+                }
+            }
+            let list = event.superior.iter().flat_map(|list| list.iter());
+            for superior in list {
+                let superior = superior.document(store).link();
+                if superiors.insert(superior) {
+                    self.subsidiaries.entry(superior).or_default().push(
+                        entity.link()
+                    );
+                }
+            }
+        }
+    }
+
+    /// Adds a country or region to the region tree.
+    ///
+    /// Only superiors that are regions themselves are used as parents.
+    /// These are the current superiors or, if there are none, those of the
+    /// most recent event naming any. This way, historic regions are filed
+    /// under the region they last belonged to.
+    ///
+    /// The roots of the tree are determined once all regions are added.
+    fn add_region(&mut self, entity: entity::Document, store: &FullStore) {
+        let regions = |superiors: Vec<entity::Document>| {
+            let mut res = Vec::new();
+            for superior in superiors {
+                if is_region(superior) && !res.contains(&superior.link()) {
+                    res.push(superior.link());
+                }
+            }
+            res
+        };
+        let mut parents = regions(
+            entity.meta().current.superior.iter().flat_map(|superior| {
+                superior.iter()
+            }).map(|superior| superior.document(store)).collect()
+        );
+        if parents.is_empty() {
+            let events = event::sorted_events(
+                entity.data().events.iter(), |event| &event.date
+            );
+            parents = events.into_iter().rev().find_map(|event| {
+                event.superior.as_ref().map(|list| {
+                    regions(
+                        list.iter().map(|superior| {
+                            superior.document(store)
+                        }).collect()
+                    )
+                })
+            }).unwrap_or_default();
+        }
+        if parents.is_empty() {
+            return
+        }
+        for parent in &parents {
+            self.region_children.entry(*parent).or_default().push(
+                entity.link()
+            );
+        }
+        self.region_parents.insert(entity.link(), parents);
+    }
+
+    /// Returns the documents citing a source.
+    pub fn citations(&self, source: source::Link) -> Option<&Citations> {
+        self.citations.get(&source)
+    }
+
+    /// Returns the lines using a path for their course.
+    pub fn path_lines(&self, path: path::Link) -> &[line::Link] {
+        self.path_lines.get(&path).map(|res| {
+            res.as_slice()
+        }).unwrap_or_default()
+    }
+
+    /// Returns the points located on a path with the index of their node.
+    pub fn path_points(&self, path: path::Link) -> &[(usize, point::Link)] {
+        self.path_points.get(&path).map(|res| {
+            res.as_slice()
+        }).unwrap_or_default()
+    }
+
+    /// Returns the lines ever owned or operated by an entity.
+    pub fn holdings(&self, entity: entity::Link) -> &[Holding] {
+        self.holdings.get(&entity).map(|res| {
+            res.as_slice()
+        }).unwrap_or_default()
+    }
+
+    /// Returns the entities naming an entity as their successor.
+    pub fn predecessors(&self, entity: entity::Link) -> &[entity::Link] {
+        self.predecessors.get(&entity).map(|res| {
+            res.as_slice()
+        }).unwrap_or_default()
+    }
+
+    /// Returns the entities naming an entity as one of their superiors.
+    pub fn subsidiaries(&self, entity: entity::Link) -> &[entity::Link] {
+        self.subsidiaries.get(&entity).map(|res| {
+            res.as_slice()
+        }).unwrap_or_default()
+    }
+
+    /// Returns the parent regions of a country or region.
+    pub fn region_parents(&self, entity: entity::Link) -> &[entity::Link] {
+        self.region_parents.get(&entity).map(|res| {
+            res.as_slice()
+        }).unwrap_or_default()
+    }
+
+    /// Returns the child regions of a country or region.
+    pub fn region_children(&self, entity: entity::Link) -> &[entity::Link] {
+        self.region_children.get(&entity).map(|res| {
+            res.as_slice()
+        }).unwrap_or_default()
+    }
+
+    /// Returns the countries and regions without a parent region.
+    pub fn region_roots(&self) -> &[entity::Link] {
+        &self.region_roots
+    }
+
+    /// Returns the structures belonging to a line or point.
+    pub fn structures(
+        &self, link: impl Into<DocumentLink>
+    ) -> &[structure::Link] {
+        self.structures.get(&link.into()).map(|res| {
+            res.as_slice()
+        }).unwrap_or_default()
+    }
+}
+
+/// Returns whether an entity is a country or region.
+pub fn is_region(entity: entity::Document) -> bool {
+    matches!(
+        entity.data().subtype.into_value(),
+        entity::Subtype::Country | entity::Subtype::Region
+    )
+}
+
+/// Returns the roots of the region tree.
+///
+/// These are all regions without parents. Since the data may contain
+/// regions whose parents form a cycle, these would never be reached from
+/// any root. For each such cycle, the first region found on it becomes a
+/// root as well.
+fn roots<T: Copy + Eq + Hash>(
+    regions: &[T], parents: &HashMap<T, Vec<T>>
+) -> Vec<T> {
+    let parents_of = |region: T| {
+        parents.get(&region).map(|res| res.as_slice()).unwrap_or_default()
+    };
+    let mut children: HashMap<T, Vec<T>> = HashMap::new();
+    for &region in regions {
+        for &parent in parents_of(region) {
+            children.entry(parent).or_default().push(region);
+        }
+    }
+
+    let mut res = Vec::new();
+    let mut reached = HashSet::new();
+    let reach = |root: T, reached: &mut HashSet<T>| {
+        let mut todo = vec![root];
+        while let Some(region) = todo.pop() {
+            if reached.insert(region) {
+                todo.extend(children.get(&region).into_iter().flatten());
+            }
+        }
+    };
+    for &region in regions {
+        if parents_of(region).is_empty() {
+            res.push(region);
+            reach(region, &mut reached);
+        }
+    }
+    for &region in regions {
+        if reached.contains(&region) {
+            continue
+        }
+        // All parents of an unreached region are unreached, too, so
+        // following the first parent has to end up going in circles.
+        let mut seen = HashSet::new();
+        let mut current = region;
+        while seen.insert(current) {
+            current = parents_of(current)[0];
+        }
+        res.push(current);
+        reach(current, &mut reached);
+    }
+    res
+}
+
+/// Returns the sources cited by a sequence of events without duplicates.
+fn cited<'a, E: Cited + 'a>(
+    events: impl Iterator<Item = &'a E>
+) -> Vec<source::Link> {
+    let mut seen = HashSet::new();
+    events.flat_map(Cited::sources).copied().filter(|link| {
+        seen.insert(*link)
+    }).collect()
+}
+
+
+//------------ Citations -----------------------------------------------------
+
+/// The documents citing a source.
+#[derive(Default)]
+pub struct Citations {
+    /// The lines citing the source in their events.
+    pub lines: Vec<line::Link>,
+
+    /// The points citing the source in their events.
+    pub points: Vec<point::Link>,
+
+    /// The entities citing the source in their events.
+    pub entities: Vec<entity::Link>,
+
+    /// The sources that are part of the source as a collection.
+    pub members: Vec<source::Link>,
+}
+
+
+//------------ Holding -------------------------------------------------------
+
+/// A line owned or operated by an entity at some point.
+#[derive(Clone, Copy)]
+pub struct Holding {
+    /// The line.
+    pub line: line::Link,
+
+    /// Whether the entity was listed as an owner of the line.
+    pub owner: bool,
+
+    /// Whether the entity was listed as an operator of the line.
+    pub operator: bool,
+}
+
+
+//------------ Tests ---------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn region_roots(parents: &[(u32, &[u32])]) -> Vec<u32> {
+        let regions: Vec<_> = parents.iter().map(|item| item.0).collect();
+        let parents = parents.iter().filter(|item| {
+            !item.1.is_empty()
+        }).map(|item| (item.0, item.1.to_vec())).collect();
+        roots(&regions, &parents)
+    }
+
+    #[test]
+    fn roots_without_cycles() {
+        assert_eq!(
+            region_roots(&[(1, &[]), (2, &[1]), (3, &[2]), (4, &[])]),
+            [1, 4]
+        );
+        assert_eq!(region_roots(&[(1, &[]), (2, &[1]), (3, &[1, 2])]), [1]);
+    }
+
+    #[test]
+    fn roots_with_cycles() {
+        // 2 and 3 are each other’s parent, 4 hangs below them.
+        assert_eq!(
+            region_roots(&[(1, &[]), (4, &[3]), (2, &[3]), (3, &[2])]),
+            [1, 3]
+        );
+
+        // A region that is its own parent.
+        assert_eq!(region_roots(&[(1, &[1]), (2, &[1])]), [1]);
+
+        // Two separate cycles.
+        assert_eq!(
+            region_roots(&[(1, &[2]), (2, &[1]), (3, &[4]), (4, &[3])]),
+            [1, 3]
+        );
+    }
+}
@@ -0,0 +1,272 @@
+//! Machine-readable names of enum values.
+//!
+//! The JSON and GeoJSON representations as well as some query parameters
+//! use these names. They are spelled out explicitly rather than derived
+//! from the `Debug` output of the raildata types, so that a renamed
+//! variant in raildata fails to compile instead of silently changing the
+//! API.
+
+use std::borrow::Cow;
+use raildata::document::{entity, line, point, source, structure};
+use raildata::types::date::Precision;
+
+
+//------------ code ----------------------------------------------------------
+
+/// Returns the machine-readable name of an enum value.
+pub fn code(value: impl Code) -> Cow<'static, str> {
+    value.code()
+}
+
+
+//------------ Code ----------------------------------------------------------
+
+/// An enum with a machine-readable name for each value.
+pub trait Code {
+    /// Returns the machine-readable name of the value.
+    fn code(self) -> Cow<'static, str>;
+}
+
+impl<T: Code + Copy> Code for &T {
+    fn code(self) -> Cow<'static, str> {
+        (*self).code()
+    }
+}
+
+/// Implements `Code` by mapping each variant to a name.
+macro_rules! code {
+    ( $type:ty { $( $variant:ident => $name:expr, )* } ) => {
+        impl Code for $type {
+            fn code(self) -> Cow<'static, str> {
+                Cow::Borrowed(match self {
+                    $( Self::$variant => $name, )*
+                })
+            }
+        }
+    }
+}
+
+code!(Precision {
+    Exact => "exact",
+    Circa => "circa",
+    Before => "before",
+    After => "after",
+});
+
+code!(line::Status {
+    None => "none",
+    Planned => "planned",
+    Construction => "construction",
+    Open => "open",
+    Suspended => "suspended",
+    Reopened => "reopened",
+    Closed => "closed",
+    Removed => "removed",
+    Released => "released",
+});
+
+code!(line::Goods {
+    None => "none",
+    Limited => "limited",
+    Full => "full",
+});
+
+code!(line::Passenger {
+    None => "none",
+    Limited => "limited",
+    Historic => "historic",
+    Seasonal => "seasonal",
+    Tourist => "tourist",
+    Full => "full",
+});
+
+code!(line::ElSystem {
+    Ole => "ole",
+    Rail => "rail",
+    Rail4 => "rail4",
+});
+
+code!(line::AcDc {
+    Ac16 => "ac16",
+    Ac25 => "ac25",
+    Ac50 => "ac50",
+    Tc50 => "tc50",
+    Dc => "dc",
+});
+
+code!(entity::Status {
+    Forming => "forming",
+    Open => "open",
+    Closed => "closed",
+});
+
+code!(point::Status {
+    Planned => "planned",
+    Construction => "construction",
+    Open => "open",
+    Reopened => "reopened",
+    Suspended => "suspended",
+    Closed => "closed",
+});
+
+code!(point::Category {
+    Border => "border",
+    DeAbzw => "de_abzw",
+    DeAnst => "de_anst",
+    DeAwanst => "de_awanst",
+    DeBf => "de_bf",
+    DeBft => "de_bft",
+    DeBk => "de_bk",
+    DeDkst => "de_dkst",
+    DeGlgr => "de_glgr",
+    DeHp => "de_hp",
+    DeHst => "de_hst",
+    DeKr => "de_kr",
+    DeKrbf => "de_krbf",
+    DeKrst => "de_krst",
+    DeLdst => "de_ldst",
+    DeMuseum => "de_museum",
+    DePo => "de_po",
+    DeStrw => "de_strw",
+    DeStw => "de_stw",
+    DeUehst => "de_uehst",
+    DeUest => "de_uest",
+    DeAhst => "de_ahst",
+    DeGnst => "de_gnst",
+    DeGa => "de_ga",
+    DeUst => "de_ust",
+    DeTp => "de_tp",
+    DeEGr => "de_egr",
+    DeGp => "de_gp",
+    DeLGr => "de_lgr",
+    DeRBGr => "de_rbgr",
+    DkB => "dk_b",
+    DkGr => "dk_gr",
+    DkSmd => "dk_smd",
+    DkSud => "dk_sud",
+    DkSt => "dk_st",
+    DkT => "dk_t",
+    DkTs => "dk_ts",
+    DkVm => "dk_vm",
+    GbHalt => "gb_halt",
+    GbJn => "gb_jn",
+    GbSt => "gb_st",
+    GbTep => "gb_tep",
+    NlAansl => "nl_aansl",
+    NlBrug => "nl_brug",
+    NlGem => "nl_gem",
+    NlH => "nl_h",
+    NlKnp => "nl_knp",
+    NlOlp => "nl_olp",
+    NlSt => "nl_st",
+    NoS => "no_s",
+    NoSp => "no_sp",
+    NoHp => "no_hp",
+});
+
+code!(point::CodeType {
+    Plc => "plc",
+    DeDs100 => "de_ds100",
+    DeDstnr => "de_dstnr",
+    DeLknr => "de_lknr",
+    DeVbl => "de_vbl",
+    DkRef => "dk_ref",
+    NlAfk => "nl_afk",
+    NoFs => "no_fs",
+    NoNjk => "no_njk",
+    NoNsb => "no_nsb",
+});
+
+code!(point::DeRang {
+    I => "i",
+    Ii => "ii",
+    Iii => "iii",
+    Iiia => "iiia",
+    Iiib => "iiib",
+    Iv => "iv",
+    V => "v",
+    Vi => "vi",
+    U => "u",
+    S => "s",
+});
+
+code!(structure::Subtype {
+    Bridge => "bridge",
+    Tunnel => "tunnel",
+});
+
+/// Line categories use the short form raildata provides.
+impl Code for line::Category {
+    fn code(self) -> Cow<'static, str> {
+        Cow::Borrowed(self.short_str())
+    }
+}
+
+/// Entity subtypes.
+///
+/// Only the subtypes the site treats specially are spelled out. Others
+/// use the snake case form of their variant name.
+impl Code for entity::Subtype {
+    fn code(self) -> Cow<'static, str> {
+        match self {
+            entity::Subtype::Country => Cow::Borrowed("country"),
+            entity::Subtype::Region => Cow::Borrowed("region"),
+            _ => Cow::Owned(snake_case(&format!("{:?}", self))),
+        }
+    }
+}
+
+/// Source subtypes.
+///
+/// Only the subtypes the site treats specially are spelled out. Others
+/// use the snake case form of their variant name.
+impl Code for source::Subtype {
+    fn code(self) -> Cow<'static, str> {
+        match self {
+            source::Subtype::Article => Cow::Borrowed("article"),
+            source::Subtype::Book => Cow::Borrowed("book"),
+            source::Subtype::Volume => Cow::Borrowed("volume"),
+            source::Subtype::Online => Cow::Borrowed("online"),
+            _ => Cow::Owned(snake_case(&format!("{:?}", self))),
+        }
+    }
+}
+
+/// Converts a camel case identifier into snake case.
+fn snake_case(ident: &str) -> String {
+    let mut res = String::with_capacity(ident.len() + 4);
+    let mut prev_lower = false;
+    for ch in ident.chars() {
+        if ch.is_uppercase() && prev_lower {
+            res.push('_');
+        }
+        prev_lower = ch.is_lowercase() || ch.is_ascii_digit();
+        res.extend(ch.to_lowercase());
+    }
+    res
+}
+
+
+//------------ Tests ---------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn snake_case_idents() {
+        assert_eq!(snake_case("Company"), "company");
+        assert_eq!(snake_case("DeDs100"), "de_ds100");
+        assert_eq!(snake_case("Rail4"), "rail4");
+        assert_eq!(snake_case("DeEGr"), "de_egr");
+    }
+
+    #[test]
+    fn explicit_codes() {
+        assert_eq!(code(line::Status::Reopened), "reopened");
+        assert_eq!(code(&point::CodeType::DeDs100), "de_ds100");
+        assert_eq!(code(point::Category::DeEGr), "de_egr");
+        assert_eq!(code(line::AcDc::Ac16), "ac16");
+        assert_eq!(code(Precision::Circa), "circa");
+    }
+}
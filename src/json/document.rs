@@ -0,0 +1,554 @@
+//! JSON representations of documents.
+
+use raildata::document::{Document, entity, line, path, point};
+use raildata::document::{source, structure};
+use raildata::store::DocumentLink;
+use raildata::types::{LanguageCode, LocalCode};
+use raildata::types::date::{Date, EventDate};
+use serde_json::{Value, json};
+use crate::geo;
+use crate::event::Cited;
+use crate::page;
+use crate::page::snip;
+use crate::route::Href;
+use crate::state::RequestState;
+use super::code::code;
+
+
+//------------ document ------------------------------------------------------
+
+/// Returns the JSON representation of a document.
+///
+/// All documents share the fields `key`, `type`, `url`, and `title`.
+/// The document’s data is kept in the field `data` and depends on the
+/// type of the document. References to other documents are given as
+/// objects with their key and URL.
+pub fn document(doc: Document, state: &RequestState) -> Value {
+    json!({
+        "key": doc.key().as_str(),
+        "type": super::doc_type(doc),
+        "url": super::attr(doc.link().href(state)),
+        "title": super::text(snip::combined::title(doc, state)),
+        "data": match doc {
+            Document::Line(line) => line_data(line, state),
+            Document::Entity(entity) => entity_data(entity, state),
+            Document::Path(path) => path_data(path, state),
+            Document::Point(point) => point_data(point, state),
+            Document::Source(source) => source_data(source, state),
+            Document::Structure(structure) => {
+                structure_data(structure, state)
+            }
+        }
+    })
+}
+
+
+//------------ link ----------------------------------------------------------
+
+/// Returns the JSON representation of a reference to another document.
+pub fn link(link: impl Into<DocumentLink>, state: &RequestState) -> Value {
+    let link = link.into();
+    json!({
+        "key": link.document(state.store()).key().as_str(),
+        "url": super::attr(link.href(state)),
+    })
+}
+
+/// Returns the JSON representation of the dates of an event.
+///
+/// Events can have more than one date if sources disagree, so this is
+/// always a list.
+fn event_date(date: &EventDate) -> Value {
+    date.iter().map(self::date).collect()
+}
+
+/// Returns the JSON representation of a date.
+///
+/// The date itself is given in ISO 8601 with the day and month left out
+/// for partial dates. Its precision and doubt are given separately.
+fn date(date: &Date) -> Value {
+    let mut iso = format!("{:04}", date.year());
+    if let Some(month) = date.month() {
+        iso.push_str(&format!("-{:02}", month));
+    }
+    if let Some(day) = date.day() {
+        iso.push_str(&format!("-{:02}", day));
+    }
+    json!({
+        "date": iso,
+        "precision": code(date.precision()),
+        "doubt": date.doubt(),
+    })
+}
+
+
+//------------ line_data -----------------------------------------------------
+
+fn line_data(line: line::Document, state: &RequestState) -> Value {
+    let current = &line.data().current;
+    let link = line.link();
+    json!({
+        "code": line.data().code().as_str(),
+        "current": {
+            "name": sections(line, &current.name, state, |name| {
+                name.as_ref().map(|name| {
+                    language_names(name.iter().map(|(code, name)| {
+                        (code.map(|x| *x.as_ref()), name.as_value().as_str())
+                    }))
+                }).into()
+            }),
+            "status": sections(line, &current.status, state, |status| {
+                code(status).into()
+            }),
+            "category": sections(line, &current.category, state, |cat| {
+                cat.iter().map(|cat| code(cat)).collect()
+            }),
+            "gauge": sections(line, &current.gauge, state, |gauge| {
+                gauge.iter().map(|gauge| gauge.gauge()).collect()
+            }),
+            "rails": sections(line, &current.rails, state, |rails| {
+                rails.to_string().into()
+            }),
+            "tracks": sections(line, &current.tracks, state, |tracks| {
+                tracks.to_string().into()
+            }),
+            "electrified": sections(line, &current.electrified, state, |el| {
+                el.as_ref().map(|el| {
+                    el.iter().map(electrified).collect::<Vec<_>>()
+                }).into()
+            }),
+            "passenger": sections(line, &current.passenger, state, |value| {
+                code(value).into()
+            }),
+            "goods": sections(line, &current.goods, state, |value| {
+                code(value).into()
+            }),
+            "owner": sections(line, &current.owner, state, |owner| {
+                owner.as_ref().map(|owner| {
+                    owner.iter().map(|item| {
+                        self::link(*item, state)
+                    }).collect::<Vec<_>>()
+                }).into()
+            }),
+            "operator": sections(line, &current.operator, state, |operator| {
+                operator.as_ref().map(|operator| {
+                    operator.iter().map(|item| {
+                        self::link(*item, state)
+                    }).collect::<Vec<_>>()
+                }).into()
+            }),
+        },
+        "points": line.data().points.iter_documents(state.store()).map(
+            |point| {
+                json!({
+                    "point": self::link(point.link(), state),
+                    "name": point.data().name_in_jurisdiction(
+                        line.data().jurisdiction()
+                    ),
+                    "location": point.data().line_location(link).map(
+                        |(location, _)| location.to_string()
+                    ),
+                    "category": point.data().category().map(|(cat, _)| {
+                        cat.map(code).collect::<Vec<_>>()
+                    }),
+                    "junction": point.meta().junction,
+                    "open": point.data().is_open(),
+                })
+            }
+        ).collect::<Vec<_>>(),
+        "events": line.data().events.iter().map(|event| {
+            line_event(line, event, state)
+        }).collect::<Vec<_>>(),
+    })
+}
+
+fn line_event(
+    line: line::Document, event: &line::Event, state: &RequestState
+) -> Value {
+    json!({
+        "date": event_date(&event.date),
+        "sections": event.sections.iter().map(|section| {
+            json!({
+                "start": link(
+                    section.start_point(line.data(), state.store()).link(),
+                    state
+                ),
+                "end": link(
+                    section.end_point(line.data(), state.store()).link(),
+                    state
+                ),
+            })
+        }).collect::<Vec<_>>(),
+        "name": event.name.as_ref().map(|name| {
+            name.as_ref().map(|name| {
+                language_names(name.iter().map(|(code, name)| {
+                    (code.map(|x| *x.as_ref()), name.as_value().as_str())
+                }))
+            })
+        }),
+        "status": event.status.map(code),
+        "category": event.category.as_ref().map(|cat| {
+            cat.iter().map(|cat| code(cat)).collect::<Vec<_>>()
+        }),
+        "gauge": event.gauge.as_ref().map(|gauge| {
+            gauge.iter().map(|gauge| gauge.gauge()).collect::<Vec<_>>()
+        }),
+        "rails": event.rails.as_ref().map(|rails| rails.to_string()),
+        "tracks": event.tracks.as_ref().map(|tracks| tracks.to_string()),
+        "electrified": event.electrified.as_ref().map(|el| {
+            el.as_ref().map(|el| {
+                el.iter().map(electrified).collect::<Vec<_>>()
+            })
+        }),
+        "passenger": event.passenger.map(code),
+        "goods": event.goods.map(code),
+        "owner": event.owner.as_ref().map(|owner| {
+            owner.as_ref().map(|owner| {
+                owner.iter().map(|item| {
+                    link(*item, state)
+                }).collect::<Vec<_>>()
+            })
+        }),
+        "operator": event.operator.as_ref().map(|operator| {
+            operator.as_ref().map(|operator| {
+                operator.iter().map(|item| {
+                    link(*item, state)
+                }).collect::<Vec<_>>()
+            })
+        }),
+        "sources": event.sources().map(|item| {
+            link(*item, state)
+        }).collect::<Vec<_>>(),
+    })
+}
+
+/// Returns the per-section values of a line property.
+fn sections<'a, T>(
+    line: line::Document<'a>,
+    value: &'a line::CurrentValue<T>,
+    state: &'a RequestState,
+    op: impl Fn(&'a T) -> Value,
+) -> Value {
+    value.as_slice().iter().map(|(section, value)| {
+        json!({
+            "start": link(
+                section.start_point(line.data(), state.store()).link(), state
+            ),
+            "end": link(
+                section.end_point(line.data(), state.store()).link(), state
+            ),
+            "value": op(value),
+        })
+    }).collect()
+}
+
+fn electrified(el: &line::Electrified) -> Value {
+    el.generic().map(|gen| {
+        json!({
+            "system": code(gen.system),
+            "voltage": gen.voltage,
+            "frequency": code(gen.frequency),
+        })
+    }).unwrap_or(Value::Null)
+}
+
+
+//------------ entity_data ---------------------------------------------------
+
+fn entity_data(entity: entity::Document, state: &RequestState) -> Value {
+    let current = &entity.meta().current;
+    json!({
+        "subtype": code(entity.data().subtype.into_value()),
+        "current": {
+            "name": current.name.as_ref().map(|name| {
+                local_names(name.iter().map(|(code, name)| {
+                    (code.map(|x| *x.as_ref()), name.as_value().as_str())
+                }))
+            }),
+            "short_name": current.short_name.as_ref().map(|name| {
+                local_names(name.iter().map(|(code, name)| {
+                    (code.map(|x| *x.as_ref()), name.as_value().as_str())
+                }))
+            }),
+            "superior": current.superior.as_ref().map(|list| {
+                list.iter().map(|item| link(*item, state)).collect::<Vec<_>>()
+            }),
+            "domicile": current.domicile.as_ref().map(|list| {
+                list.iter().map(|item| link(*item, state)).collect::<Vec<_>>()
+            }),
+            "owner": current.owner.as_ref().map(|list| {
+                list.iter().map(|item| link(*item, state)).collect::<Vec<_>>()
+            }),
+            "status": current.status.as_ref().map(|status| {
+                code(status.to_value())
+            }),
+            "successor": current.successor.as_ref().map(|successor| {
+                link(*successor, state)
+            }),
+        },
+        "events": entity.data().events.iter().map(|event| {
+            entity_event(event, state)
+        }).collect::<Vec<_>>(),
+    })
+}
+
+fn entity_event(event: &entity::Event, state: &RequestState) -> Value {
+    json!({
+        "date": event_date(&event.date),
+        "name": event.name.as_ref().map(|name| {
+            local_names(name.iter().map(|(code, name)| {
+                (code.map(|x| *x.as_ref()), name.as_value().as_str())
+            }))
+        }),
+        "short_name": event.short_name.as_ref().map(|name| {
+            local_names(name.iter().map(|(code, name)| {
+                (code.map(|x| *x.as_ref()), name.as_value().as_str())
+            }))
+        }),
+        "superior": event.superior.as_ref().map(|list| {
+            list.iter().map(|item| link(*item, state)).collect::<Vec<_>>()
+        }),
+        "domicile": event.domicile.as_ref().map(|list| {
+            list.iter().map(|item| link(*item, state)).collect::<Vec<_>>()
+        }),
+        "owner": event.owner.as_ref().map(|list| {
+            list.iter().map(|item| link(*item, state)).collect::<Vec<_>>()
+        }),
+        "status": event.status.as_ref().map(|status| {
+            code(status.to_value())
+        }),
+        "successor": event.successor.as_ref().map(|successor| {
+            link(*successor, state)
+        }),
+        "sources": event.sources().map(|item| {
+            link(*item, state)
+        }).collect::<Vec<_>>(),
+    })
+}
+
+
+//------------ path_data -----------------------------------------------------
+
+fn path_data(path: path::Document, state: &RequestState) -> Value {
+    let coords = geo::path_coords(path);
+    json!({
+        "nodes": coords.len(),
+        "coordinates": super::geojson::coordinates(&coords),
+        "length": geo::length(&coords),
+        "lines": page::path::lines(path, state).into_iter().map(
+            |line| self::link(line.link(), state)
+        ).collect::<Vec<_>>(),
+    })
+}
+
+
+//------------ point_data ----------------------------------------------------
+
+fn point_data(point: point::Document, state: &RequestState) -> Value {
+    let current = &point.meta().current;
+    json!({
+        "current": {
+            "status": current.status.as_ref().map(|status| {
+                code(status.to_value())
+            }),
+            "name": current.name.as_ref().map(|name| {
+                local_names(name.iter().map(|(code, name)| {
+                    (code.map(|x| *x.as_ref()), name.as_value().as_str())
+                }))
+            }),
+            "short_name": current.short_name.as_ref().map(|name| {
+                local_names(name.iter().map(|(code, name)| {
+                    (code.map(|x| *x.as_ref()), name.as_value().as_str())
+                }))
+            }),
+            "public_name": current.public_name.as_ref().map(|name| {
+                local_names(
+                    name.iter().map(|name| name.iter()).flatten().map(
+                        |(code, name)| (
+                            code.map(|x| *x.as_ref()),
+                            name.as_value().as_str()
+                        )
+                    )
+                )
+            }),
+            "designation": current.designation.as_ref().map(|name| {
+                local_names(name.iter().map(|(code, name)| {
+                    (code.map(|x| *x.as_ref()), name.as_value().as_str())
+                }))
+            }),
+            "de_name16": current.de_name16.as_ref().map(|name| name.as_str()),
+            "category": current.category.as_ref().map(|category| {
+                category.iter().map(|cat| {
+                    code(cat.into_value())
+                }).collect::<Vec<_>>()
+            }),
+            "de_rang": current.de_rang.as_ref().map(|rang| {
+                code(rang.into_value())
+            }),
+            "superior": current.superior.as_ref().and_then(|sup| {
+                sup.as_ref()
+            }).map(|sup| {
+                sup.iter().map(|item| link(*item, state)).collect::<Vec<_>>()
+            }),
+            "codes": current.codes.iter().map(|(code_type, value)| {(
+                code(code_type).into_owned(),
+                value.map(|item| item.to_string()).collect()
+            )}).collect::<serde_json::Map<_, _>>(),
+            "location": current.location.iter().map(|(line, loc)| {
+                json!({
+                    "line": link(*line, state),
+                    "location": loc,
+                })
+            }).collect::<Vec<_>>(),
+        },
+        "lines": point.xrefs().lines.iter().map(|line| {
+            link(*line, state)
+        }).collect::<Vec<_>>(),
+        "events": point.data().events.iter().map(|event| {
+            point_event(event, state)
+        }).collect::<Vec<_>>(),
+    })
+}
+
+fn point_event(event: &point::Event, state: &RequestState) -> Value {
+    json!({
+        "date": event_date(&event.date),
+        "status": event.status.as_ref().map(|status| {
+            code(status.to_value())
+        }),
+        "name": event.name.as_ref().map(|name| {
+            local_names(name.iter().map(|(code, name)| {
+                (code.map(|x| *x.as_ref()), name.as_value().as_str())
+            }))
+        }),
+        "short_name": event.short_name.as_ref().map(|name| {
+            local_names(name.iter().map(|(code, name)| {
+                (code.map(|x| *x.as_ref()), name.as_value().as_str())
+            }))
+        }),
+        "designation": event.designation.as_ref().map(|name| {
+            local_names(name.iter().map(|(code, name)| {
+                (code.map(|x| *x.as_ref()), name.as_value().as_str())
+            }))
+        }),
+        "de_name16": event.de_name16.as_ref().map(|name| name.as_str()),
+        "category": event.category.as_ref().map(|category| {
+            category.iter().map(|cat| {
+                code(cat.into_value())
+            }).collect::<Vec<_>>()
+        }),
+        "de_rang": event.de_rang.as_ref().map(|rang| {
+            code(rang.into_value())
+        }),
+        "codes": event.codes.iter().map(|(code_type, value)| {(
+            code(code_type).into_owned(),
+            value.map(|item| item.to_string()).collect()
+        )}).collect::<serde_json::Map<_, _>>(),
+        "sources": event.sources().map(|item| {
+            link(*item, state)
+        }).collect::<Vec<_>>(),
+    })
+}
+
+/// Returns a map from local codes to names.
+///
+/// Names without a local code are keyed by the empty string.
+fn local_names<'a>(
+    names: impl Iterator<Item = (Option<LocalCode>, &'a str)>
+) -> Value {
+    names.map(|(code, name)| {(
+        code.map(|code| code.as_str().to_string()).unwrap_or_default(),
+        Value::from(name)
+    )}).collect::<serde_json::Map<_, _>>().into()
+}
+
+/// Returns a map from language codes to names.
+///
+/// Names without a language code are keyed by the empty string.
+fn language_names<'a>(
+    names: impl Iterator<Item = (Option<LanguageCode>, &'a str)>
+) -> Value {
+    names.map(|(code, name)| {(
+        code.map(|code| code.as_str().to_string()).unwrap_or_default(),
+        Value::from(name)
+    )}).collect::<serde_json::Map<_, _>>().into()
+}
+
+
+//------------ source_data ---------------------------------------------------
+
+fn source_data(source: source::Document, state: &RequestState) -> Value {
+    let data = source.data();
+    json!({
+        "subtype": code(data.subtype.into_value()),
+        "author": data.author.iter().map(|item| {
+            self::link(*item, state)
+        }).collect::<Vec<_>>(),
+        "editor": data.editor.iter().map(|item| {
+            self::link(*item, state)
+        }).collect::<Vec<_>>(),
+        "title": data.title.as_ref().map(|title| title.as_str()),
+        "short_title": data.short_title.as_ref().map(|title| {
+            title.as_str()
+        }),
+        "collection": data.collection.as_ref().map(|item| {
+            self::link(*item, state)
+        }),
+        "volume": data.volume.as_ref().map(|volume| volume.as_str()),
+        "edition": data.edition.as_ref().map(|edition| edition.as_str()),
+        "publisher": data.publisher.iter().map(|item| {
+            self::link(*item, state)
+        }).collect::<Vec<_>>(),
+        "date": data.date.as_ref().map(self::date),
+        "pages": data.pages.as_ref().map(|pages| pages.to_string()),
+        "isbn": data.isbn.as_ref().map(|isbn| isbn.to_string()),
+        "url": data.url.as_ref().map(|url| url.as_str()),
+        "also": data.also.iter().map(|item| {
+            self::link(*item, state)
+        }).collect::<Vec<_>>(),
+    })
+}
+
+
+//------------ structure_data ------------------------------------------------
+
+fn structure_data(
+    structure: structure::Document, state: &RequestState
+) -> Value {
+    json!({
+        "subtype": code(structure.data().subtype.into_value()),
+        "length": page::structure::last(structure, |event| {
+            event.length.as_ref()
+        }).map(|(_, length)| length.to_string()),
+        "lines": page::structure::lines(structure, state).into_iter().map(
+            |line| self::link(line.link(), state)
+        ).collect::<Vec<_>>(),
+        "points": page::structure::points(structure, state).into_iter().map(
+            |point| self::link(point.link(), state)
+        ).collect::<Vec<_>>(),
+        "events": structure.data().events.iter().map(|event| {
+            structure_event(event, state)
+        }).collect::<Vec<_>>(),
+    })
+}
+
+fn structure_event(event: &structure::Event, state: &RequestState) -> Value {
+    json!({
+        "date": event_date(&event.date),
+        "name": event.name.as_ref().map(|name| {
+            language_names(name.iter().map(|(code, name)| {
+                (code.map(|x| *x.as_ref()), name.as_value().as_str())
+            }))
+        }),
+        "length": event.length.as_ref().map(|length| length.to_string()),
+        "lines": event.lines.iter().map(|item| {
+            link(*item, state)
+        }).collect::<Vec<_>>(),
+        "points": event.points.iter().map(|item| {
+            link(*item, state)
+        }).collect::<Vec<_>>(),
+        "sources": event.sources().map(|item| {
+            link(*item, state)
+        }).collect::<Vec<_>>(),
+    })
+}
@@ -0,0 +1,211 @@
+//! GeoJSON representations of documents.
+//!
+//! See RFC 7946 for the format.
+
+use std::collections::HashSet;
+use raildata::document::{Document, entity, line, path, point};
+use serde_json::{Value, json};
+use crate::geo;
+use crate::geo::Coord;
+use crate::page::snip;
+use crate::route::Href;
+use crate::state::RequestState;
+use super::code::code;
+use super::document::link;
+
+
+//------------ document ------------------------------------------------------
+
+/// Returns the GeoJSON representation of a document if it has one.
+///
+/// Lines, paths, points, and regions have a GeoJSON representation.
+pub fn document(doc: Document, state: &RequestState) -> Option<Value> {
+    match doc {
+        Document::Line(line) => Some(self::line(line, state)),
+        Document::Path(path) => Some(self::path(path, state)),
+        Document::Point(point) => Some(self::point(point, state)),
+        Document::Entity(entity) => {
+            match entity.data().subtype.into_value() {
+                entity::Subtype::Country | entity::Subtype::Region => {
+                    Some(region(entity, state))
+                }
+                _ => None
+            }
+        }
+        _ => None
+    }
+}
+
+
+//------------ line ----------------------------------------------------------
+
+/// Returns a line as a feature collection.
+///
+/// The collection contains a feature for each point of the line that has a
+/// location and a line string feature for each segment of the line’s
+/// course.
+pub fn line(line: line::Document, state: &RequestState) -> Value {
+    let link = line.link();
+    let jurisdiction = line.data().jurisdiction();
+    let points = line.data().points.iter_documents(
+        state.store()
+    ).filter_map(|point| {
+        let coord = geo::point_coord(point, state.store())?;
+        Some(feature(
+            point_geometry(coord),
+            json!({
+                "key": point.key().as_str(),
+                "url": super::attr(point.href(state)),
+                "name": point.data().name_in_jurisdiction(jurisdiction),
+                "location": point.data().line_location(link).map(
+                    |(location, _)| location.to_string()
+                ),
+                "category": point.data().category().map(|(cat, _)| {
+                    cat.map(code).collect::<Vec<_>>()
+                }),
+                "junction": point.meta().junction,
+                "open": point.data().is_open(),
+            })
+        ))
+    });
+    let course = geo::line_course(line, state.store()).into_iter().map(
+        |coords| {
+            feature(
+                json!({
+                    "type": "LineString",
+                    "coordinates": coordinates(&coords),
+                }),
+                line_properties(line, state)
+            )
+        }
+    );
+    collection(course.chain(points).collect())
+}
+
+/// Returns a line as a single feature with its course as the geometry.
+fn line_feature(line: line::Document, state: &RequestState) -> Value {
+    feature(
+        json!({
+            "type": "MultiLineString",
+            "coordinates": geo::line_course(
+                line, state.store()
+            ).iter().map(|coords| coordinates(coords)).collect::<Vec<_>>(),
+        }),
+        line_properties(line, state)
+    )
+}
+
+fn line_properties(line: line::Document, state: &RequestState) -> Value {
+    json!({
+        "key": line.key().as_str(),
+        "url": super::attr(line.href(state)),
+        "code": line.data().code().as_str(),
+        "title": super::text(snip::line::line_title(line, state)),
+        "status": line.data().current.status.as_slice().last().map(
+            |(_, status)| code(status)
+        ),
+    })
+}
+
+
+//------------ path ----------------------------------------------------------
+
+/// Returns a path as a feature with its nodes as a line string.
+pub fn path(path: path::Document, state: &RequestState) -> Value {
+    let coords = geo::path_coords(path);
+    feature(
+        json!({
+            "type": "LineString",
+            "coordinates": coordinates(&coords),
+        }),
+        json!({
+            "key": path.key().as_str(),
+            "url": super::attr(path.href(state)),
+            "length": geo::length(&coords),
+        })
+    )
+}
+
+
+//------------ point ---------------------------------------------------------
+
+/// Returns a point as a feature.
+///
+/// The properties contain the point’s current state. If the point has no
+/// location, the geometry is null.
+pub fn point(point: point::Document, state: &RequestState) -> Value {
+    let current = &point.meta().current;
+    feature(
+        geo::point_coord(
+            point, state.store()
+        ).map(point_geometry).unwrap_or(Value::Null),
+        json!({
+            "key": point.key().as_str(),
+            "url": super::attr(point.href(state)),
+            "name": super::text(snip::point::title(point, state)),
+            "status": current.status.as_ref().map(|status| {
+                code(status.to_value())
+            }),
+            "category": current.category.as_ref().map(|category| {
+                category.iter().map(|cat| {
+                    code(cat.into_value())
+                }).collect::<Vec<_>>()
+            }),
+            "codes": current.codes.iter().map(|(code_type, value)| {(
+                code(code_type).into_owned(),
+                value.map(|item| item.to_string()).collect()
+            )}).collect::<serde_json::Map<_, _>>(),
+            "lines": point.xrefs().lines.iter().map(|line| {
+                link(*line, state)
+            }).collect::<Vec<_>>(),
+        })
+    )
+}
+
+
+//------------ region --------------------------------------------------------
+
+/// Returns a region as a collection of the lines in it.
+///
+/// A line may be listed with several of its sections. It is still only
+/// included once.
+pub fn region(entity: entity::Document, state: &RequestState) -> Value {
+    let mut seen = HashSet::new();
+    collection(
+        entity.xrefs().line_regions.iter().filter(|(link, _)| {
+            seen.insert(*link)
+        }).map(|(link, _)| {
+            line_feature(link.document(state.store()), state)
+        }).collect()
+    )
+}
+
+
+//------------ Helpers -------------------------------------------------------
+
+fn collection(features: Vec<Value>) -> Value {
+    json!({
+        "type": "FeatureCollection",
+        "features": features,
+    })
+}
+
+fn feature(geometry: Value, properties: Value) -> Value {
+    json!({
+        "type": "Feature",
+        "geometry": geometry,
+        "properties": properties,
+    })
+}
+
+fn point_geometry(coord: Coord) -> Value {
+    json!({
+        "type": "Point",
+        "coordinates": [coord.lon, coord.lat],
+    })
+}
+
+/// Returns a list of coordinates as GeoJSON positions.
+pub fn coordinates(coords: &[Coord]) -> Value {
+    coords.iter().map(|coord| json!([coord.lon, coord.lat])).collect()
+}
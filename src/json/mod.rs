@@ -0,0 +1,152 @@
+//! JSON representations of our data.
+
+use htmlfn::core::{AttributeValue, Content, Target};
+use httools::response::{ContentType, Response};
+use raildata::document::Document;
+use serde::Serialize;
+use crate::state::RequestState;
+
+pub mod code;
+pub mod document;
+pub mod geojson;
+pub mod search;
+
+
+//------------ response ------------------------------------------------------
+
+/// Creates a response with the JSON serialization of `value`.
+pub fn response<T: Serialize>(value: &T, state: &RequestState) -> Response {
+    state.response().content_type(ContentType::JSON).body(to_vec(value))
+}
+
+/// Returns the JSON serialization of `value`.
+pub fn to_vec<T: Serialize>(value: &T) -> Vec<u8> {
+    serde_json::to_vec(value).expect("JSON serialization failed")
+}
+
+
+//------------ Helpers -------------------------------------------------------
+
+/// Renders text content into a plain string.
+///
+/// The content is rendered as HTML and then unescaped again, so the
+/// result is suitable for places that do their own escaping, such as JSON
+/// or SVG.
+pub fn text(content: impl Content) -> String {
+    let mut target = Target::new();
+    content.render_content(&mut target);
+    unescape(&String::from_utf8_lossy(target.as_ref()))
+}
+
+/// Renders an attribute value such as an href into a plain string.
+pub fn attr(value: impl AttributeValue) -> String {
+    let mut target = Target::new();
+    value.render_attr_value(&mut target);
+    unescape(&String::from_utf8_lossy(target.as_ref()))
+}
+
+/// Escapes a plain string for inclusion in HTML.
+///
+/// This is for the few JSON values that scripts insert into the page as
+/// HTML. It is safe both in text content and in quoted attribute values.
+pub fn escape_html(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '&' => res.push_str("&amp;"),
+            '<' => res.push_str("&lt;"),
+            '>' => res.push_str("&gt;"),
+            '"' => res.push_str("&quot;"),
+            '\'' => res.push_str("&#39;"),
+            ch => res.push(ch),
+        }
+    }
+    res
+}
+
+/// Replaces the character references in rendered HTML.
+///
+/// Besides numeric references, only the named references produced by
+/// escaping are supported. Anything else is left as is.
+fn unescape(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find('&') {
+        res.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        let ch = rest.find(';').and_then(|end| {
+            let ch = match &rest[1..end] {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                name => {
+                    let num = name.strip_prefix('#')?;
+                    match num.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                        None => num.parse().ok(),
+                    }.and_then(char::from_u32)
+                }
+            };
+            ch.map(|ch| (ch, end))
+        });
+        match ch {
+            Some((ch, end)) => {
+                res.push(ch);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                res.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    res.push_str(rest);
+    res
+}
+
+/// Returns the type name of a document.
+pub fn doc_type(doc: Document) -> &'static str {
+    match doc {
+        Document::Line(_) => "line",
+        Document::Entity(_) => "entity",
+        Document::Path(_) => "path",
+        Document::Point(_) => "point",
+        Document::Source(_) => "source",
+        Document::Structure(_) => "structure",
+    }
+}
+
+
+//------------ Tests ---------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unescape_references() {
+        assert_eq!(unescape("Foo &amp; Bar"), "Foo & Bar");
+        assert_eq!(unescape("&lt;a&gt; &quot;b&quot;"), "<a> \"b\"");
+        assert_eq!(unescape("&#39;&#x2013;&#X2013;"), "'––");
+        assert_eq!(unescape("Köln"), "Köln");
+    }
+
+    #[test]
+    fn escape_html_round_trip() {
+        let s = "<a href=\"x\">Tom & Jerry's</a>";
+        assert_eq!(
+            escape_html(s),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+        );
+        assert_eq!(unescape(&escape_html(s)), s);
+    }
+
+    #[test]
+    fn unescape_leaves_unknown() {
+        assert_eq!(unescape("a & b"), "a & b");
+        assert_eq!(unescape("&nbsp;&"), "&nbsp;&");
+        assert_eq!(unescape("&#xZZ; &amp"), "&#xZZ; &amp");
+    }
+}
@@ -0,0 +1,107 @@
+//! The search API.
+
+use serde::Serialize;
+use crate::page::snip;
+use crate::route::Href;
+use crate::state::RequestState;
+
+
+//------------ search --------------------------------------------------------
+
+/// The maximum number of items returned.
+const MAX_ITEMS: usize = 20;
+
+pub fn search(state: &RequestState) -> SearchResult {
+    let term = state.query().get_first("q").unwrap_or("");
+    SearchResult {
+        items: if term.is_empty() {
+            Vec::new()
+        }
+        else {
+            state.catalogue().search_name(term).take(MAX_ITEMS).map(
+                |(name, link)| {
+                    let doc = link.document(state.store());
+                    SearchItem::new(
+                        &super::attr(link.href(state)),
+                        super::doc_type(doc),
+                        &super::text(snip::combined::title(doc, state)),
+                        &name,
+                        doc.key().as_str(),
+                    )
+                }
+            ).collect()
+        }
+    }
+}
+
+
+//------------ SearchResult --------------------------------------------------
+
+#[derive(Serialize)]
+pub struct SearchResult {
+    items: Vec<SearchItem>,
+}
+
+/// A document found by the search.
+///
+/// The search box inserts the strings straight into the page, so they are
+/// all HTML-escaped.
+#[derive(Serialize)]
+pub struct SearchItem {
+    /// The URL of the document’s page.
+    url: String,
+
+    /// The type of the document.
+    #[serde(rename = "type")]
+    doc_type: &'static str,
+
+    /// The title of the document.
+    title: String,
+
+    /// The name that matched the search term.
+    name: String,
+
+    /// The key of the document.
+    key: String,
+}
+
+impl SearchItem {
+    /// Creates a new item from plain strings, escaping them.
+    fn new(
+        url: &str, doc_type: &'static str, title: &str, name: &str, key: &str
+    ) -> Self {
+        SearchItem {
+            url: super::escape_html(url),
+            doc_type,
+            title: super::escape_html(title),
+            name: super::escape_html(name),
+            key: super::escape_html(key),
+        }
+    }
+}
+
+
+//------------ Tests ---------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn item_is_escaped() {
+        let item = SearchItem::new(
+            "/line/\"x", "line", "<script>alert(1)</script> & \"Co\"",
+            "A & B", "line.de.<b>",
+        );
+        assert_eq!(item.url, "/line/&quot;x");
+        assert_eq!(
+            item.title,
+            "&lt;script&gt;alert(1)&lt;/script&gt; &amp; &quot;Co&quot;"
+        );
+        assert_eq!(item.name, "A &amp; B");
+        assert_eq!(item.key, "line.de.&lt;b&gt;");
+        let json = String::from_utf8(crate::json::to_vec(&item)).unwrap();
+        assert!(!json.contains('<'));
+        assert!(!json.contains("\\\""));
+    }
+}
@@ -1,5 +1,4 @@
 use htmlfn::core::{AttributeValue, Target};
-//use raildata::types::date::{Date, Precision};
 use raildata::types::LanguageCode;
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
@@ -68,67 +67,3 @@ impl From<Lang> for LanguageCode {
         }
     }
 }
-
-/*
-impl Lang {
-    pub fn render_date(self, date: &Date, tmpl: &mut TemplateBuffer) {
-        match self {
-            Lang::De => {
-                match date.precision() {
-                    Precision::Exact => { }
-                    Precision::Circa => { write!(tmpl, "ca. "); }
-                    Precision::Before => { write!(tmpl, "vor "); }
-                    Precision::After => { write!(tmpl, "nach "); }
-                }
-                if let Some(day) = date.day() {
-                    write!(tmpl, "{}. ", day);
-                }
-                if let Some(month) = date.month() {
-                    write!(tmpl, "{}", roman(month));
-                }
-                write!(tmpl, "{}", date.year());
-                if date.doubt() {
-                    write!(tmpl, " ?");
-                }
-            }
-            Lang::En => {
-                match date.precision() {
-                    Precision::Exact => { }
-                    Precision::Circa => { write!(tmpl, "c. "); }
-                    Precision::Before => { write!(tmpl, "before "); }
-                    Precision::After => { write!(tmpl, "after "); }
-                }
-                write!(tmpl, "{:04}", date.year());
-                if let Some(month) = date.month() {
-                    write!(tmpl, "-{:02}", month);
-                }
-                if let Some(day) = date.day() {
-                    write!(tmpl, "-{:02}", day);
-                }
-                if date.doubt() {
-                    write!(tmpl, " ?");
-                }
-            }
-        }
-    }
-}
-
-
-fn roman(val: u8) -> &'static str {
-    match val {
-        1 => "I. ",
-        2 => "II. ",
-        3 => "III. ",
-        4 => "IV. ",
-        5 => "V. ",
-        6 => "VI. ",
-        7 => "VII. ",
-        8 => "VIII. ",
-        9 => "IX. ",
-        10 => "X. ",
-        11 => "XI. ",
-        12 => "XII. ",
-        _ => ""
-    }
-}
-*/
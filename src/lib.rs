@@ -1,8 +1,18 @@
+pub mod cite;
 pub mod config;
+pub mod csv;
+pub mod event;
+pub mod export;
+pub mod geo;
+pub mod gpx;
 pub mod i18n;
+pub mod index;
+pub mod json;
 pub mod lang;
 pub mod page;
+pub mod reload;
 pub mod route;
 pub mod server;
 pub mod state;
+pub mod svg;
 
@@ -1,6 +1,9 @@
 use std::env::current_dir;
-use clap::{Command, crate_authors, crate_version};
-use railsite::server;
+use std::path::PathBuf;
+use clap::{
+    Arg, ArgAction, Command, crate_authors, crate_version, value_parser
+};
+use railsite::{export, reload, server};
 use railsite::config::Config;
 use railsite::state::ServerState;
 
@@ -13,31 +16,72 @@ async fn main() {
                 "Fatal: cannot get current directory ({}). Aborting.",
                 err
             );
-            return
+            std::process::exit(1)
         }
     };
 
-    let config = Config::from_arg_matches(
-        &Config::config_args(
-            Command::new("railsite")
-                .version(crate_version!())
-                .author(crate_authors!())
-                .about("the railwayhistory.org server")
-        ).get_matches(),
-        &cur_dir
-    );
-    let config = match config {
+    let matches = Config::config_args(
+        Command::new("railsite")
+            .version(crate_version!())
+            .author(crate_authors!())
+            .about("the railwayhistory.org server")
+            .subcommand(
+                Command::new("export")
+                    .about(
+                        "exports the site as static HTML files to be served \
+                         by a web server"
+                    )
+                    .arg(
+                        Arg::new("dir")
+                            .required(true)
+                            .value_name("DIR")
+                            .value_parser(value_parser!(PathBuf))
+                            .help("the directory to write the site to")
+                    )
+                    .arg(
+                        Arg::new("allow-degraded")
+                            .long("allow-degraded")
+                            .action(ArgAction::SetTrue)
+                            .help("export even if the database is incomplete")
+                    )
+            )
+    ).get_matches();
+
+    let config = match Config::from_arg_matches(&matches, &cur_dir) {
         Ok(config) => config,
-        Err(_) => return
+        Err(_) => std::process::exit(1)
     };
 
     let state = match ServerState::load(&config) {
         Ok(state) => state.into_arc(),
-        Err(_) => return
+        Err(_) => std::process::exit(1)
     };
 
+    if let Some(("export", matches)) = matches.subcommand() {
+        let dir = cur_dir.join(
+            matches.get_one::<PathBuf>("dir").expect("required argument")
+        );
+        let degraded = state.database().is_degraded();
+        if degraded && !matches.get_flag("allow-degraded") {
+            eprintln!(
+                "Fatal: the database was loaded in degraded mode. \
+                 Use --allow-degraded to export anyway."
+            );
+            std::process::exit(1)
+        }
+        if export::export(state, &dir).is_err() {
+            std::process::exit(1)
+        }
+        return
+    }
+
+    tokio::spawn({
+        let config = config.clone();
+        let state = state.clone();
+        async move { reload::watch(&config, state).await }
+    });
+
     eprintln!("Listening on {}", config.listen);
 
     server::serve(&config, state).await
 }
-
@@ -0,0 +1,133 @@
+use std::path::Path;
+use std::sync::Arc;
+use htmlfn::html;
+use htmlfn::core::Content;
+use htmlfn::utils::{display, either, iter};
+use raildata::store::DocumentLink;
+use crate::i18n;
+use crate::page::frame;
+use crate::page::snip::date::UtcTime;
+use crate::route::Href;
+use crate::state::{LoadErrors, RequestState};
+
+//------------ page ----------------------------------------------------------
+
+pub fn page(state: &RequestState) -> impl frame::Page + '_ {
+    let history = state.server().error_history();
+    frame::standard(state, i18n::term::admin::errors::title(state), (), (), (
+        html::h1(i18n::term::admin::errors::title(state)),
+        excluded(state),
+        either(history.is_empty(),
+            || html::p(i18n::term::admin::errors::none(state)),
+            || iter(history.into_iter().map(|errors| {
+                report(errors, state)
+            }))
+        ),
+    ))
+}
+
+
+//------------ excluded ------------------------------------------------------
+
+/// The list of files left out of the current database.
+fn excluded(state: &RequestState) -> impl Content + '_ {
+    let excluded = state.database().excluded();
+    (!excluded.is_empty()).then(|| {(
+        html::h2(i18n::term::admin::errors::excluded(state)),
+        html::ul::class("admin-errors-excluded",
+            iter(excluded.iter().map(|path| {
+                html::li(html::tt(display(path.display())))
+            }))
+        ),
+    )})
+}
+
+
+//------------ report --------------------------------------------------------
+
+/// A single error report grouped by stage and file.
+fn report(
+    errors: Arc<LoadErrors>, state: &RequestState
+) -> impl Content + '_ {
+    let groups = group(&errors, state);
+    html::div::class("admin-errors-report", (
+        html::h2((
+            display(UtcTime(errors.time())),
+            " – ",
+            display(errors.report().len()),
+            " ",
+            i18n::term::admin::errors::count(state),
+        )),
+        iter(groups.into_iter().map(move |(stage, files)| {(
+            html::h3(stage),
+            iter(files.into_iter().map(move |file| {(
+                html::h4::class("admin-errors-file", (
+                    html::tt(file.path),
+                    file.link.map(|link| {(
+                        " → ",
+                        html::a(
+                            link.href(state),
+                            link.document(state.store()).key().as_str()
+                        ),
+                    )}),
+                )),
+                html::ul::class("admin-errors-messages",
+                    iter(file.messages.into_iter().map(|msg| html::li(msg)))
+                ),
+            )}))
+        )}))
+    ))
+}
+
+/// The errors of one file.
+struct FileErrors {
+    /// The path of the file as given by the report.
+    path: String,
+
+    /// The document stored in the file if it exists.
+    link: Option<DocumentLink>,
+
+    /// The error messages.
+    messages: Vec<String>,
+}
+
+/// Groups the errors of a report by stage and then by file.
+///
+/// Since the report is sorted, we only need to group consecutive items.
+fn group(
+    errors: &LoadErrors, state: &RequestState
+) -> Vec<(String, Vec<FileErrors>)> {
+    let mut res: Vec<(String, Vec<FileErrors>)> = Vec::new();
+    for item in errors.report().iter() {
+        let stage = format!("{:?}", item.stage());
+        let path = item.origin().path();
+        if res.last().map(|last| last.0 != stage).unwrap_or(true) {
+            res.push((stage, Vec::new()));
+        }
+        let files = &mut res.last_mut().unwrap().1;
+        let path_str = path.map(|path| {
+            path.display().to_string()
+        }).unwrap_or_default();
+        if files.last().map(|last| last.path != path_str).unwrap_or(true) {
+            files.push(FileErrors {
+                link: path.and_then(|path| document(path, errors, state)),
+                path: path_str,
+                messages: Vec::new(),
+            });
+        }
+        files.last_mut().unwrap().messages.push(item.to_string());
+    }
+    res
+}
+
+/// Returns the document for the file at `path` if there is one.
+///
+/// The path is relative to the directory loading was attempted from. The
+/// document is looked up in the current database by its path relative to
+/// the directory it was loaded from, so this works for degraded loads from
+/// the mirror, too.
+fn document(
+    path: &Path, errors: &LoadErrors, state: &RequestState
+) -> Option<DocumentLink> {
+    state.database().document_at(path.strip_prefix(errors.base()).ok()?)
+}
@@ -0,0 +1,4 @@
+
+pub use self::errors::page as errors;
+
+mod errors;
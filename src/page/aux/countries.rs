@@ -1,9 +1,10 @@
 use htmlfn::html;
 use htmlfn::core::Content;
-use htmlfn::utils::iter;
+use htmlfn::utils::{either, iter};
+use raildata::document::entity;
 use crate::i18n;
-use crate::page::frame;
-use crate::route::Href;
+use crate::page::{frame, snip};
+use crate::page::region::hierarchy::Tree;
 use crate::state::RequestState;
 
 //------------ page ----------------------------------------------------------
@@ -15,22 +16,66 @@ pub fn page(state: &RequestState) -> impl frame::Page + '_ {
     ))
 }
 
+/// The countries and regions as an expandable tree.
+///
+/// The top level contains the regions without a parent, i.e., usually the
+/// countries. Their child regions can be expanded and list all further
+/// descendants with the depth marked via classes.
 fn index(state: &RequestState) -> impl Content + '_ {
-    let mut countries = state.catalogue().countries.values().map(|link| {
-        (link, link.document(state.store()))
+    let tree = Tree::new(state);
+    let roots = tree.roots().into_iter().map(|root| {
+        let children = tree.children(root).into_iter().map(|child| {
+            (child, tree.descendants(child))
+        }).collect::<Vec<_>>();
+        (root, children)
     }).collect::<Vec<_>>();
-    countries.sort_by_key(|(_, doc)| {
-        doc.data().local_short_name(state.lang().into())
-    });
-    html::ul(
-        iter(countries.into_iter().map(|(link, doc)| {
+    html::ul::class("countries-index",
+        iter(roots.into_iter().map(move |(root, children)| {
             html::li(
-                html::a(
-                    link.href(state),
-                    doc.data().local_short_name(state.lang().into())
+                either(children.is_empty(),
+                    move || snip::entity::link(root, state),
+                    move || html::details((
+                        html::summary(snip::entity::link(root, state)),
+                        html::ul(
+                            iter(children.into_iter().map(
+                                move |(child, descendants)| {
+                                    region(child, descendants, state)
+                                }
+                            ))
+                        ),
+                    ))
                 )
             )
         }))
     )
 }
 
+fn region<'a>(
+    region: entity::Document<'a>,
+    descendants: Vec<(usize, entity::Document<'a>)>,
+    state: &'a RequestState
+) -> impl Content + 'a {
+    html::li(
+        either(descendants.is_empty(),
+            move || snip::entity::link(region, state),
+            move || html::details((
+                html::summary(snip::entity::link(region, state)),
+                html::ul(
+                    iter(descendants.into_iter().map(move |(depth, item)| {
+                        html::li::class(
+                            DEPTH_CLASSES[depth.min(DEPTH_CLASSES.len() - 1)],
+                            snip::entity::link(item, state)
+                        )
+                    }))
+                ),
+            ))
+        )
+    )
+}
+
+const DEPTH_CLASSES: [&str; 4] = [
+    "countries-index-depth-0",
+    "countries-index-depth-1",
+    "countries-index-depth-2",
+    "countries-index-depth-3",
+];
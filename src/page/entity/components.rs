@@ -1,12 +1,16 @@
 use htmlfn::html;
 use htmlfn::core::{Content, Text};
 use raildata::document::entity;
+use crate::{i18n, route};
 use crate::state::RequestState;
 
 
 #[derive(Clone, Copy)]
 pub enum Chapter {
     Overview,
+    Lines,
+    Points,
+    Lineage,
 }
 
 impl Chapter {
@@ -19,7 +23,41 @@ impl Chapter {
     pub fn headline<'a>(
         self, entity: entity::Document<'a>, state: &'a RequestState
     ) -> impl Content + 'a {
-        html::h1(entity.data().local_short_name(state.lang().into()))
+        (
+            html::h1(entity.data().local_short_name(state.lang().into())),
+            html::div::class("standard-subpage-nav",
+                html::ul((
+                    html::li::class(
+                        matches!(self, Self::Overview).then(|| "active"),
+                        html::a(
+                            route::entity::Overview::href(entity, state),
+                            i18n::term::entity::subpage::overview(state)
+                        )
+                    ),
+                    html::li::class(
+                        matches!(self, Self::Lines).then(|| "active"),
+                        html::a(
+                            route::entity::Lines::href(entity, state),
+                            i18n::term::entity::subpage::lines(state)
+                        )
+                    ),
+                    html::li::class(
+                        matches!(self, Self::Points).then(|| "active"),
+                        html::a(
+                            route::entity::Points::href(entity, state),
+                            i18n::term::entity::subpage::points(state)
+                        )
+                    ),
+                    html::li::class(
+                        matches!(self, Self::Lineage).then(|| "active"),
+                        html::a(
+                            route::entity::Lineage::href(entity, state),
+                            i18n::term::entity::subpage::lineage(state)
+                        )
+                    ),
+                ))
+            )
+        )
     }
 }
 
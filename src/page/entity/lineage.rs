@@ -0,0 +1,444 @@
+//! The lineage of an entity.
+//!
+//! The lineage follows the successor relation of entities backwards to the
+//! predecessors and forwards to the successors, and the superior relation
+//! upwards to the parents and downwards to the subsidiaries. Entities only
+//! record their successor and their superiors, so the other direction is
+//! looked up in the index.
+
+use htmlfn::html;
+use htmlfn::core::Content;
+use htmlfn::utils::{either, iter};
+use raildata::document::entity;
+use raildata::types::date::Date;
+use crate::{event, i18n, json, route};
+use crate::page::{frame, snip};
+use crate::page::snip::date::LocalRange;
+use crate::route::Href;
+use crate::state::RequestState;
+use crate::svg::Svg;
+use super::components::Chapter;
+
+const CHAPTER: Chapter = Chapter::Lineage;
+
+pub fn page<'a>(
+    entity: entity::Document<'a>, state: &'a RequestState
+) -> impl frame::Page + 'a {
+    let lineage = Lineage::new(entity, state);
+    let empty = lineage.is_empty();
+    let groups = lineage.groups();
+    let diagram = draw(entity, &groups, state).inline();
+    frame::standard(state, CHAPTER.title(entity, state), (), (), (
+        CHAPTER.headline(entity, state),
+        either(empty,
+            || html::p(i18n::term::entity::lineage::empty(state)),
+            move || (
+                html::div::class("entity-lineage-diagram", (
+                    diagram,
+                    html::p(html::a(
+                        route::entity::LineageSvg::href(entity, state),
+                        i18n::term::entity::lineage::download(state)
+                    )),
+                )),
+                iter(groups.into_iter().filter(|(_, items)| {
+                    !items.is_empty()
+                }).map(move |(group, items)| {(
+                    html::h2(group.title(state)),
+                    html::ul::class("entity-lineage",
+                        iter(items.into_iter().map(move |item| {
+                            list_item(item, state)
+                        }))
+                    ),
+                )}))
+            )
+        )
+    ))
+}
+
+/// An item of the nested list.
+///
+/// Since the tree is flattened, nesting is expressed through classes for
+/// the depth.
+fn list_item<'a>(
+    item: Item<'a>, state: &'a RequestState
+) -> impl Content + 'a {
+    let depth = item.depth.min(DEPTH_CLASSES.len() - 1);
+    html::li::class(DEPTH_CLASSES[depth], (
+        snip::entity::link(item.entity, state),
+        (item.since.is_some() || item.until.is_some()).then(|| {(
+            " (", snip::date::range(item.since, item.until, state), ")",
+        )}),
+    ))
+}
+
+const DEPTH_CLASSES: [&str; 6] = [
+    "entity-lineage-depth-0",
+    "entity-lineage-depth-1",
+    "entity-lineage-depth-2",
+    "entity-lineage-depth-3",
+    "entity-lineage-depth-4",
+    "entity-lineage-depth-5",
+];
+
+
+//------------ Lineage -------------------------------------------------------
+
+/// The lineage of an entity.
+///
+/// Each direction is kept as a tree flattened into a list in depth-first
+/// order. Each entity appears only once per direction even if there are
+/// several ways to reach it.
+pub struct Lineage<'a> {
+    /// The entities succeeded by the entity.
+    pub predecessors: Vec<Item<'a>>,
+
+    /// The entities succeeding the entity.
+    pub successors: Vec<Item<'a>>,
+
+    /// The entities the entity is or was part of.
+    pub parents: Vec<Item<'a>>,
+
+    /// The entities that are or were part of the entity.
+    pub subsidiaries: Vec<Item<'a>>,
+}
+
+impl<'a> Lineage<'a> {
+    pub fn new(entity: entity::Document<'a>, state: &'a RequestState) -> Self {
+        let relations = Relations::new(state);
+        let mut res = Lineage {
+            predecessors: Vec::new(),
+            successors: Vec::new(),
+            parents: Vec::new(),
+            subsidiaries: Vec::new(),
+        };
+        walk(entity, 0, &mut vec![entity.link()], &mut res.predecessors,
+            &|entity| relations.predecessors(entity)
+        );
+        walk(entity, 0, &mut vec![entity.link()], &mut res.successors,
+            &|entity| relations.successors(entity)
+        );
+        walk(entity, 0, &mut vec![entity.link()], &mut res.parents,
+            &|entity| relations.parents(entity)
+        );
+        walk(entity, 0, &mut vec![entity.link()], &mut res.subsidiaries,
+            &|entity| relations.subsidiaries(entity)
+        );
+        res
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.predecessors.is_empty() && self.successors.is_empty()
+            && self.parents.is_empty() && self.subsidiaries.is_empty()
+    }
+
+    /// Returns the four directions of the lineage.
+    pub fn groups(self) -> [(Group, Vec<Item<'a>>); 4] {
+        [
+            (Group::Predecessors, self.predecessors),
+            (Group::Successors, self.successors),
+            (Group::Parents, self.parents),
+            (Group::Subsidiaries, self.subsidiaries),
+        ]
+    }
+}
+
+/// Adds the entities related to `entity` to `res`, depth first.
+///
+/// The entities already visited are kept in `seen` so that cycles in the
+/// data don’t send us into an endless loop.
+fn walk<'a>(
+    entity: entity::Document<'a>,
+    depth: usize,
+    seen: &mut Vec<entity::Link>,
+    res: &mut Vec<Item<'a>>,
+    next: &impl Fn(entity::Document<'a>) -> Vec<Item<'a>>,
+) {
+    for mut item in next(entity) {
+        if seen.contains(&item.entity.link()) {
+            continue
+        }
+        seen.push(item.entity.link());
+        item.depth = depth;
+        let entity = item.entity;
+        res.push(item);
+        walk(entity, depth + 1, seen, res, next);
+    }
+}
+
+
+//------------ Item ----------------------------------------------------------
+
+/// An entity in the lineage.
+#[derive(Clone, Copy)]
+pub struct Item<'a> {
+    /// The depth of the entity in the tree, starting at 0.
+    pub depth: usize,
+
+    /// The entity.
+    pub entity: entity::Document<'a>,
+
+    /// The date the relation was established if known.
+    pub since: Option<&'a Date>,
+
+    /// The date the relation ended if known.
+    pub until: Option<&'a Date>,
+}
+
+impl<'a> Item<'a> {
+    fn new(
+        entity: entity::Document<'a>,
+        since: Option<&'a Date>,
+        until: Option<&'a Date>,
+    ) -> Self {
+        Item { depth: 0, entity, since, until }
+    }
+}
+
+
+//------------ Group ---------------------------------------------------------
+
+/// The direction of a part of the lineage.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Group {
+    Predecessors,
+    Successors,
+    Parents,
+    Subsidiaries,
+}
+
+impl Group {
+    pub fn title(self, state: &RequestState) -> &'static str {
+        match self {
+            Group::Predecessors => {
+                i18n::term::entity::lineage::predecessors(state)
+            }
+            Group::Successors => {
+                i18n::term::entity::lineage::successors(state)
+            }
+            Group::Parents => {
+                i18n::term::entity::lineage::parents(state)
+            }
+            Group::Subsidiaries => {
+                i18n::term::entity::lineage::subsidiaries(state)
+            }
+        }
+    }
+}
+
+
+//------------ Relations -----------------------------------------------------
+
+/// Access to the successor and superior relations of entities.
+///
+/// The forward direction is taken from the events of the entity itself,
+/// the backward direction from the entities listed in the index.
+struct Relations<'a> {
+    state: &'a RequestState,
+}
+
+impl<'a> Relations<'a> {
+    fn new(state: &'a RequestState) -> Self {
+        Relations { state }
+    }
+
+    /// Returns the successors of an entity with the date of the succession.
+    fn successions(
+        &self, entity: entity::Document<'a>
+    ) -> Vec<(entity::Document<'a>, Option<&'a Date>)> {
+        let store = self.state.store();
+        let events = event::sorted_events(
+            entity.data().events.iter(), |event| &event.date
+        );
+        events.into_iter().filter_map(|event| {
+            event.successor.as_ref().map(|successor| {
+                (successor.document(store), event.date.iter().next())
+            })
+        }).collect()
+    }
+
+    /// Returns the superiors of an entity with the dates of the relation.
+    ///
+    /// A relation lasts from the event listing the superior until the next
+    /// event no longer listing it, with events taken in order of date.
+    fn superiors(
+        &self, entity: entity::Document<'a>
+    ) -> Vec<(entity::Document<'a>, Option<&'a Date>, Option<&'a Date>)> {
+        let store = self.state.store();
+        let mut res = Vec::new();
+
+        // The superiors currently in effect with their start date.
+        let mut current: Vec<(entity::Document, Option<&Date>)> = Vec::new();
+        let events = event::sorted_events(
+            entity.data().events.iter(), |event| &event.date
+        );
+        for event in events {
+            let date = event.date.iter().next();
+            if let Some(superior) = event.superior.as_ref() {
+                let new: Vec<_> = superior.iter().map(|link| {
+                    link.document(store)
+                }).collect();
+                current.retain(|(sup, since)| {
+                    if new.iter().any(|item| item.link() == sup.link()) {
+                        return true
+                    }
+                    res.push((*sup, *since, date));
+                    false
+                });
+                for sup in new {
+                    if !current.iter().any(|item| {
+                        item.0.link() == sup.link()
+                    }) {
+                        current.push((sup, date))
+                    }
+                }
+            }
+        }
+        res.extend(current.into_iter().map(|(sup, since)| {
+            (sup, since, None)
+        }));
+        res
+    }
+
+    fn predecessors(&self, entity: entity::Document<'a>) -> Vec<Item<'a>> {
+        let store = self.state.store();
+        self.state.index().predecessors(entity.link()).iter().flat_map(
+            |link| {
+                let other = link.document(store);
+                self.successions(other).into_iter().filter(|item| {
+                    item.0.link() == entity.link()
+                }).map(move |(_, date)| Item::new(other, date, None))
+            }
+        ).collect()
+    }
+
+    fn successors(&self, entity: entity::Document<'a>) -> Vec<Item<'a>> {
+        self.successions(entity).into_iter().map(|(other, date)| {
+            Item::new(other, date, None)
+        }).collect()
+    }
+
+    fn parents(&self, entity: entity::Document<'a>) -> Vec<Item<'a>> {
+        self.superiors(entity).into_iter().map(|(other, since, until)| {
+            Item::new(other, since, until)
+        }).collect()
+    }
+
+    fn subsidiaries(&self, entity: entity::Document<'a>) -> Vec<Item<'a>> {
+        let store = self.state.store();
+        self.state.index().subsidiaries(entity.link()).iter().flat_map(
+            |link| {
+                let other = link.document(store);
+                self.superiors(other).into_iter().filter(|item| {
+                    item.0.link() == entity.link()
+                }).map(move |(_, since, until)| {
+                    Item::new(other, since, until)
+                })
+            }
+        ).collect()
+    }
+}
+
+
+//------------ svg -----------------------------------------------------------
+
+const WIDTH: f64 = 720.;
+const MARGIN: f64 = 20.;
+const ROW: f64 = 24.;
+
+/// The horizontal distance between two levels of the tree.
+const INDENT: f64 = 28.;
+
+const CLASS: &str = "lineage-diagram";
+
+const STYLE: &str = "\
+    .lineage-diagram text { font: 12px sans-serif; fill: #2e3436; }\
+    .lineage-diagram .root { font-weight: bold; }\
+    .lineage-diagram .group { font-weight: bold; fill: #1d3053; }\
+    .lineage-diagram .date { fill: #555; }\
+    .lineage-diagram .edge { \
+        fill: none; stroke: #1d3053; stroke-width: 1.5; \
+    }\
+    .lineage-diagram .node { \
+        fill: #fff; stroke: #1d3053; stroke-width: 2; \
+    }\
+";
+
+/// Draws the lineage of an entity as a tree.
+///
+/// The entity itself sits at the top. Below it, each direction of the
+/// lineage follows as a separate tree with the relation dates next to the
+/// names.
+pub fn svg(entity: entity::Document, state: &RequestState) -> Svg {
+    draw(entity, &Lineage::new(entity, state).groups(), state)
+}
+
+/// Draws the already collected groups of a lineage.
+fn draw(
+    entity: entity::Document,
+    groups: &[(Group, Vec<Item>)],
+    state: &RequestState,
+) -> Svg {
+    let rows = 1 + groups.iter().filter(|(_, items)| {
+        !items.is_empty()
+    }).map(|(_, items)| items.len() + 1).sum::<usize>();
+
+    let mut svg = Svg::new(WIDTH, 2. * MARGIN + ROW * rows as f64);
+    svg.set_class(CLASS);
+    svg.style(STYLE);
+    svg.text(
+        (MARGIN, y(0) + 4.), 0., "root",
+        &json::text(snip::entity::title(entity, state))
+    );
+
+    let mut row = 1;
+    for (group, items) in groups {
+        if items.is_empty() {
+            continue
+        }
+        svg.text((MARGIN, y(row) + 4.), 0., "group", group.title(state));
+        let group_row = row;
+        row += 1;
+
+        // The row of the last item seen at each depth for the edges.
+        let mut parents = vec![group_row];
+        for item in items {
+            parents.truncate(item.depth + 1);
+            let parent_row = parents[item.depth];
+            let x = x(item.depth + 1);
+            svg.polyline(
+                [
+                    (x - INDENT / 2., y(parent_row) + 6.),
+                    (x - INDENT / 2., y(row)),
+                    (x - 5., y(row)),
+                ],
+                "edge"
+            );
+            svg.start_link(&json::attr(item.entity.href(state)));
+            svg.circle((x, y(row)), 4., "node");
+            svg.text(
+                (x + 10., y(row) + 4.), 0., "name",
+                &json::text(snip::entity::title(item.entity, state))
+            );
+            svg.end_link();
+            if item.since.is_some() || item.until.is_some() {
+                svg.text(
+                    (WIDTH - MARGIN - 180., y(row) + 4.), 0., "date",
+                    &LocalRange::new(
+                        item.since, item.until, state.lang()
+                    ).to_string()
+                );
+            }
+            parents.push(row);
+            row += 1;
+        }
+    }
+    svg
+}
+
+fn x(depth: usize) -> f64 {
+    MARGIN + INDENT * depth as f64
+}
+
+fn y(row: usize) -> f64 {
+    MARGIN + ROW * row as f64 + ROW / 2.
+}
@@ -0,0 +1,320 @@
+use htmlfn::html;
+use htmlfn::core::Content;
+use htmlfn::utils::{either, iter};
+use raildata::document::{entity, line, point};
+use crate::{event, i18n, index};
+use crate::page::{frame, snip};
+use crate::page::line::dated::Segments;
+use crate::state::RequestState;
+use super::components::Chapter;
+
+const CHAPTER: Chapter = Chapter::Lines;
+
+pub fn page<'a>(
+    entity: entity::Document<'a>, state: &'a RequestState
+) -> impl frame::Page + 'a {
+    let holdings = holdings(entity, state);
+    frame::standard(state, CHAPTER.title(entity, state), (), (), (
+        CHAPTER.headline(entity, state),
+        either(holdings.is_empty(),
+            || html::p(i18n::term::entity::lines::empty(state)),
+            move || html::table::class("entity-lines", (
+                html::tr((
+                    html::th(i18n::term::entity::lines::line(state)),
+                    html::th(i18n::term::entity::lines::role(state)),
+                    html::th(i18n::term::entity::lines::section(state)),
+                    html::th(i18n::term::entity::lines::period(state)),
+                )),
+                iter(holdings.into_iter().map(move |holding| {
+                    item(holding, state)
+                }))
+            ))
+        )
+    ))
+}
+
+fn item<'a>(
+    holding: Holding<'a>, state: &'a RequestState
+) -> impl Content + 'a {
+    let line = holding.line;
+    let role = holding.role;
+    let jurisdiction = line.data().jurisdiction();
+    iter(holding.tenures.into_iter().enumerate().map(move |(idx, tenure)| {
+        html::tr::class(
+            if tenure.until.is_some() { "entity-lines-past" } else { "" },
+            (
+                html::td((idx == 0).then(|| snip::line::link(line, state))),
+                html::td(role.name(state)),
+                html::td((
+                    snip::point::link(tenure.start(), jurisdiction, state),
+                    " – ",
+                    snip::point::link(tenure.end(), jurisdiction, state),
+                )),
+                html::td(snip::date::range(
+                    tenure.since.date.iter().next(),
+                    tenure.until.and_then(|until| until.date.iter().next()),
+                    state
+                )),
+            )
+        )
+    }))
+}
+
+
+//------------ Holding -------------------------------------------------------
+
+/// A line owned or operated by an entity.
+pub struct Holding<'a> {
+    /// The line.
+    pub line: line::Document<'a>,
+
+    /// What the entity did with the line.
+    pub role: Role,
+
+    /// The stretches of the line held by the entity over time.
+    pub tenures: Vec<Tenure<'a>>,
+}
+
+/// Returns all lines ever owned or operated by an entity.
+///
+/// Lines don’t have cross references to their owners and operators, so
+/// the candidates are taken from the database’s index.
+pub fn holdings<'a>(
+    entity: entity::Document<'a>, state: &'a RequestState
+) -> Vec<Holding<'a>> {
+    let mut res = Vec::new();
+    for held in state.index().holdings(entity.link()) {
+        let line = held.line.document(state.store());
+        for role in Role::ALL {
+            if !role.listed(held) {
+                continue
+            }
+            let tenures = tenures(line, |event| {
+                role.holds(entity, event)
+            }, state);
+            if !tenures.is_empty() {
+                res.push(Holding { line, role, tenures })
+            }
+        }
+    }
+    res
+}
+
+
+//------------ Role ----------------------------------------------------------
+
+/// The role of an entity for a line.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    Owner,
+    Operator,
+}
+
+impl Role {
+    pub const ALL: [Self; 2] = [Role::Owner, Role::Operator];
+
+    /// Returns whether the entity is ever listed in the role.
+    fn listed(self, held: &index::Holding) -> bool {
+        match self {
+            Role::Owner => held.owner,
+            Role::Operator => held.operator,
+        }
+    }
+
+    /// Returns whether the entity has the role after an event.
+    ///
+    /// Returns `None` if the event doesn’t change the role at all.
+    fn holds(
+        self, entity: entity::Document, event: &line::Event
+    ) -> Option<bool> {
+        let list = match self {
+            Role::Owner => event.owner.as_ref()?,
+            Role::Operator => event.operator.as_ref()?,
+        };
+        Some(list.as_ref().map(|list| {
+            list.iter().any(|item| *item == entity.link())
+        }).unwrap_or(false))
+    }
+
+    pub fn name(self, state: &RequestState) -> &'static str {
+        match self {
+            Role::Owner => i18n::term::line::property::owner(state),
+            Role::Operator => i18n::term::line::property::operator(state),
+        }
+    }
+}
+
+
+//------------ Tenure --------------------------------------------------------
+
+/// A stretch of a line held by someone over a period of time.
+///
+/// What exactly “held” means is up to the caller of [`tenures`]. Here, it
+/// is an entity owning or operating the line.
+pub struct Tenure<'a> {
+    /// The points of the stretch from its start to its end.
+    pub points: Vec<point::Document<'a>>,
+
+    /// The event that started the tenure.
+    pub since: &'a line::Event,
+
+    /// The event that ended the tenure if it has ended.
+    pub until: Option<&'a line::Event>,
+}
+
+impl<'a> Tenure<'a> {
+    /// Returns the first point of the stretch.
+    pub fn start(&self) -> point::Document<'a> {
+        self.points[0]
+    }
+
+    /// Returns the last point of the stretch.
+    pub fn end(&self) -> point::Document<'a> {
+        self.points[self.points.len() - 1]
+    }
+}
+
+/// Returns the tenures of a line.
+///
+/// The closure `holds` is called for each event of the line in the order
+/// of their dates. It returns `None` if the event doesn’t concern the
+/// tenure at all or whether the event establishes it for the affected
+/// segments. Tenures are returned in the order they started and, within
+/// the same event, along the line.
+pub fn tenures<'a>(
+    line: line::Document<'a>,
+    holds: impl Fn(&line::Event) -> Option<bool>,
+    state: &'a RequestState,
+) -> Vec<Tenure<'a>> {
+    let events = event::sorted_events(
+        line.data().events.iter(), |event| &event.date
+    );
+    let segments = Segments::new(line, None, state);
+    let changes = events.iter().enumerate().filter_map(|(idx, event)| {
+        holds(event).map(|holds| {
+            (idx, holds, segments.affected(event, state))
+        })
+    });
+    runs(segments.count(), changes).into_iter().map(|run| {
+        Tenure {
+            points: segments.points()[run.start..=run.end].to_vec(),
+            since: events[run.since],
+            until: run.until.map(|until| events[until]),
+        }
+    }).collect()
+}
+
+
+//------------ Run -----------------------------------------------------------
+
+/// A run of consecutive segments held over the same period.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Run {
+    /// The index of the first segment.
+    start: usize,
+
+    /// The index of the segment after the last one.
+    end: usize,
+
+    /// The index of the event starting the tenure.
+    since: usize,
+
+    /// The index of the event ending the tenure if it has ended.
+    until: Option<usize>,
+}
+
+/// Determines the runs of a line with `count` segments.
+///
+/// Each item of `changes` contains the index of an event, whether it
+/// establishes or ends the tenure, and the ranges of segments it affects.
+/// They must be given in the order the events happened.
+fn runs(
+    count: usize,
+    changes: impl IntoIterator<Item = (usize, bool, Vec<(usize, usize)>)>
+) -> Vec<Run> {
+    // Segment index, start event index, end event index.
+    let mut found: Vec<(usize, usize, Option<usize>)> = Vec::new();
+    let mut held: Vec<Option<usize>> = vec![None; count];
+    for (event_idx, holds, affected) in changes {
+        for (start, end) in affected {
+            for idx in start..end.min(count) {
+                match (held[idx], holds) {
+                    (None, true) => held[idx] = Some(event_idx),
+                    (Some(since), false) => {
+                        found.push((idx, since, Some(event_idx)));
+                        held[idx] = None;
+                    }
+                    _ => { }
+                }
+            }
+        }
+    }
+    found.extend(held.into_iter().enumerate().filter_map(|(idx, since)| {
+        since.map(|since| (idx, since, None))
+    }));
+    found.sort_by_key(|&(idx, since, until)| (since, until, idx));
+
+    let mut res: Vec<Run> = Vec::new();
+    for (idx, since, until) in found {
+        if let Some(last) = res.last_mut() {
+            if last.end == idx && last.since == since && last.until == until {
+                last.end = idx + 1;
+                continue
+            }
+        }
+        res.push(Run { start: idx, end: idx + 1, since, until });
+    }
+    res
+}
+
+
+//------------ Tests ---------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn run(
+        start: usize, end: usize, since: usize, until: Option<usize>
+    ) -> Run {
+        Run { start, end, since, until }
+    }
+
+    #[test]
+    fn whole_line() {
+        assert_eq!(
+            runs(3, [(0, true, vec![(0, 3)])]),
+            [run(0, 3, 0, None)]
+        );
+        assert_eq!(runs(3, [(0, false, vec![(0, 3)])]), []);
+    }
+
+    #[test]
+    fn partly_ended() {
+        assert_eq!(
+            runs(3, [(0, true, vec![(0, 3)]), (1, false, vec![(1, 2)])]),
+            [
+                run(0, 1, 0, None),
+                run(2, 3, 0, None),
+                run(1, 2, 0, Some(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn held_again() {
+        assert_eq!(
+            runs(2, [
+                (0, true, vec![(0, 2)]),
+                (1, false, vec![(0, 2)]),
+                (2, true, vec![(0, 1)]),
+                (4, true, vec![(0, 2)]),
+            ]),
+            [
+                run(0, 2, 0, Some(1)),
+                run(0, 1, 2, None),
+                run(1, 2, 4, None),
+            ]
+        );
+    }
+}
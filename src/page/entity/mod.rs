@@ -1,6 +1,11 @@
-
 pub use self::overview::page as overview;
+pub use self::lineage::page as lineage;
+pub use self::lines::page as lines;
+pub use self::points::page as points;
 
 mod components;
+pub mod lineage;
+mod lines;
 mod overview;
+mod points;
 mod property;
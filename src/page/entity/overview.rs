@@ -1,9 +1,11 @@
 use htmlfn::html;
 use htmlfn::core::Content;
 use htmlfn::utils::{iter, join};
-use raildata::document::entity;
+use raildata::document::{Document, entity};
 use crate::i18n;
+use crate::event::Cited;
 use crate::page::{frame, snip};
+use crate::page::snip::source::Footnotes;
 use crate::state::RequestState;
 use super::components::Chapter;
 use super::property;
@@ -13,97 +15,137 @@ const CHAPTER: Chapter = Chapter::Overview;
 pub fn page<'a>(
     entity: entity::Document<'a>, state: &'a RequestState
 ) -> impl frame::Page + 'a {
+    let notes = footnotes(entity, state);
     frame::standard(state, CHAPTER.title(entity, state), (), (), (
         CHAPTER.headline(entity, state),
-        current(entity, state),
+        current(entity, &notes, state),
+        notes.list(state),
+        snip::cite::cite(Document::Entity(entity), state),
     ))
 }
 
 pub fn current<'a>(
     entity: entity::Document<'a>,
+    notes: &Footnotes<'a>,
     state: &'a RequestState
 ) -> impl Content + 'a {
     let current = &entity.meta().current;
+    let events = &entity.data().events;
     (
         html::h2(i18n::term::entity::properties(state)),
         html::dl::class("entity-current", (
             // Name
             current.name.as_ref().map(|name| {(
                 html::dt(i18n::term::entity::property::name(state)),
-                html::dd(
+                html::dd((
                     html::dl::class("local-names",
                         iter(name.iter().map(|(code, name)| {(
                             html::dt(snip::local::opt_local_code(code, state)),
                             html::dd(name.as_value().as_str()),
                         )}))
-                    )
-                )
+                    ),
+                    notes.current_refs(events.iter(), |event| {
+                        event.name.is_some()
+                    }, state),
+                ))
             )}),
 
             // Short Name
             current.short_name.as_ref().map(|name| {(
                 html::dt(i18n::term::entity::property::short_name(state)),
-                html::dd(
+                html::dd((
                     html::dl::class("local-names",
                         iter(name.iter().map(|(code, name)| {(
                             html::dt(snip::local::opt_local_code(code, state)),
                             html::dd(name.as_value().as_str()),
                         )}))
-                    )
-                )
+                    ),
+                    notes.current_refs(events.iter(), |event| {
+                        event.short_name.is_some()
+                    }, state),
+                ))
             )}),
 
             // Superior
             current.superior.as_ref().map(|superior| {(
                 html::dt(i18n::term::entity::property::superior(state)),
-                html::dd(
+                html::dd((
                     join(", ", superior.iter().map(|superior| {
                         snip::entity::link(
                             superior.document(state.store()), state
                         )
-                    }))
-                )
+                    })),
+                    notes.current_refs(events.iter(), |event| {
+                        event.superior.is_some()
+                    }, state),
+                ))
             )}),
 
             // Domicile
             current.domicile.as_ref().map(|domicile| {(
                 html::dt(i18n::term::entity::property::domicile(state)),
-                html::dd(
+                html::dd((
                     join(", ", domicile.iter().map(|domicile| {
                         snip::entity::link(
                             domicile.document(state.store()), state
                         )
-                    }))
-                )
+                    })),
+                    notes.current_refs(events.iter(), |event| {
+                        event.domicile.is_some()
+                    }, state),
+                ))
             )}),
 
             // Owner
             current.owner.as_ref().map(|owner| {(
                 html::dt(i18n::term::entity::property::owner(state)),
-                html::dd(
+                html::dd((
                     join(", ", owner.iter().map(|owner| {
                         snip::entity::link(
                             owner.document(state.store()), state
                         )
-                    }))
-                )
+                    })),
+                    notes.current_refs(events.iter(), |event| {
+                        event.owner.is_some()
+                    }, state),
+                ))
             )}),
 
             // Status
             current.status.as_ref().map(|status| {(
                 html::dt(i18n::term::entity::property::status(state)),
-                html::dd(property::status(status.to_value(), state)),
+                html::dd((
+                    property::status(status.to_value(), state),
+                    notes.current_refs(events.iter(), |event| {
+                        event.status.is_some()
+                    }, state),
+                )),
             )}),
 
             // Successor
             current.successor.as_ref().map(|successor| {(
                 html::dt(i18n::term::entity::property::successor(state)),
-                html::dd(
+                html::dd((
                     snip::entity::link(
                         successor.document(state.store()), state
-                    )
-                )
+                    ),
+                    notes.current_refs(events.iter(), |event| {
+                        event.successor.is_some()
+                    }, state),
+                ))
             )})
         ))
     )
 }
+
+
+//------------ Sources -------------------------------------------------------
+
+/// Returns the footnotes for all sources cited by the entity’s events.
+pub fn footnotes<'a>(
+    entity: entity::Document<'a>, state: &'a RequestState
+) -> Footnotes<'a> {
+    Footnotes::new(
+        entity.data().events.iter().flat_map(Cited::sources), state
+    )
+}
@@ -0,0 +1,118 @@
+use std::collections::{HashMap, HashSet};
+use htmlfn::html;
+use htmlfn::core::Content;
+use htmlfn::utils::{either, iter, join};
+use raildata::document::{entity, line, point, structure};
+use crate::i18n;
+use crate::page::{frame, snip};
+use crate::route::Href;
+use crate::state::RequestState;
+use super::components::Chapter;
+use super::lines::{Holding, holdings};
+
+const CHAPTER: Chapter = Chapter::Points;
+
+pub fn page<'a>(
+    entity: entity::Document<'a>, state: &'a RequestState
+) -> impl frame::Page + 'a {
+    let holdings = holdings(entity, state);
+    let structures = structures(&holdings, state);
+    let points = points(holdings);
+    frame::standard(state, CHAPTER.title(entity, state), (), (), (
+        CHAPTER.headline(entity, state),
+        html::h2(i18n::term::entity::points(state)),
+        either(points.is_empty(),
+            || html::p(i18n::term::entity::points::empty(state)),
+            move || html::ul::class("entity-points",
+                iter(points.into_iter().map(move |(point, lines)| {
+                    html::li((
+                        snip::point::link(point, None, state),
+                        " (",
+                        join(", ", lines.into_iter().map(move |line| {
+                            snip::line::code_link(line, state)
+                        })),
+                        ")",
+                    ))
+                }))
+            )
+        ),
+        html::h2(i18n::term::entity::structures(state)),
+        either(structures.is_empty(),
+            || html::p(i18n::term::entity::structures::empty(state)),
+            move || html::ul::class("entity-structures",
+                iter(structures.into_iter().map(move |structure| {
+                    structure_item(structure, state)
+                }))
+            )
+        ),
+    ))
+}
+
+fn structure_item<'a>(
+    structure: structure::Document<'a>, state: &'a RequestState
+) -> impl Content + 'a {
+    html::li((
+        html::a(
+            structure.href(state),
+            snip::structure::title(structure, state)
+        ),
+        " (",
+        i18n::enums::structure::subtype(
+            structure.data().subtype.into_value(), state
+        ),
+        ")",
+    ))
+}
+
+
+//------------ points --------------------------------------------------------
+
+/// Returns the points on all stretches of lines held by an entity.
+///
+/// Each point is returned only once together with the lines it was held
+/// on. Points are returned in the order they are first encountered.
+pub fn points<'a>(
+    holdings: Vec<Holding<'a>>
+) -> Vec<(point::Document<'a>, Vec<line::Document<'a>>)> {
+    let mut res: Vec<(point::Document, Vec<line::Document>)> = Vec::new();
+    let mut index = HashMap::new();
+    for holding in holdings {
+        let line = holding.line;
+        for point in holding.tenures.into_iter().flat_map(|tenure| {
+            tenure.points
+        }) {
+            match index.get(&point.link()) {
+                Some(&idx) => {
+                    let lines: &mut Vec<line::Document> = &mut res[idx].1;
+                    if lines.last().map(|item| {
+                        item.link() != line.link()
+                    }).unwrap_or(true) {
+                        lines.push(line)
+                    }
+                }
+                None => {
+                    index.insert(point.link(), res.len());
+                    res.push((point, vec![line]))
+                }
+            }
+        }
+    }
+    res
+}
+
+
+//------------ structures ----------------------------------------------------
+
+/// Returns all structures on lines held by an entity.
+///
+/// Each structure is returned only once.
+pub fn structures<'a>(
+    holdings: &[Holding<'a>], state: &'a RequestState
+) -> Vec<structure::Document<'a>> {
+    let mut seen = HashSet::new();
+    holdings.iter().flat_map(|holding| {
+        state.index().structures(holding.line.link()).iter()
+    }).filter(|link| seen.insert(**link)).map(|link| {
+        link.document(state.store())
+    }).collect()
+}
@@ -11,6 +11,9 @@ use crate::state::RequestState;
 
 /// A trait for any page.
 pub trait Page: Into<Body> {
+    /// Renders the page into its HTML bytes.
+    fn render_bytes(self) -> Vec<u8>;
+
     fn response(self, builder: ResponseBuilder) -> Response {
         builder.content_type(ContentType::HTML).body(self.into())
     }
@@ -126,5 +129,9 @@ impl<Cont: Content> Into<Body> for Frame<Cont> {
     }
 }
 
-impl<Cont: Content> Page for Frame<Cont> { }
+impl<Cont: Content> Page for Frame<Cont> {
+    fn render_bytes(self) -> Vec<u8> {
+        self.0.render().as_ref().into()
+    }
+}
 
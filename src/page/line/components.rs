@@ -0,0 +1,85 @@
+use htmlfn::html;
+use htmlfn::core::{Content, Text};
+use raildata::document::line;
+use crate::{i18n, route};
+use crate::page::snip;
+use crate::state::RequestState;
+
+
+#[derive(Clone, Copy)]
+pub enum Chapter {
+    Overview,
+    Route,
+    History,
+    Sources,
+    Map,
+}
+
+impl Chapter {
+    pub fn title<'a>(
+        self, line: line::Document<'a>, state: &'a RequestState
+    ) -> impl Text + 'a {
+        snip::line::title(line, state)
+    }
+
+    pub fn headline<'a>(
+        self, line: line::Document<'a>, state: &'a RequestState
+    ) -> impl Content + 'a {
+        let jurisdiction = line.data().jurisdiction();
+        (
+            html::h1::class("line-headline", (
+                html::span::class("line-headline-code", (
+                    line.data().code().as_str(), ". "
+                )),
+                html::span::class("line-headline-course", (
+                    line.data().points.first_junction(
+                        state.store()
+                    ).data().name_in_jurisdiction(jurisdiction),
+                    " – ",
+                    line.data().points.last_junction(
+                        state.store()
+                    ).data().name_in_jurisdiction(jurisdiction),
+                ))
+            )),
+            html::div::class("standard-subpage-nav",
+                html::ul((
+                    html::li::class(
+                        matches!(self, Self::Overview).then(|| "active"),
+                        html::a(
+                            route::line::Overview::href(line, state),
+                            i18n::term::line::subpage::overview(state)
+                        )
+                    ),
+                    html::li::class(
+                        matches!(self, Self::Route).then(|| "active"),
+                        html::a(
+                            route::line::Route::href(line, state),
+                            i18n::term::line::subpage::route(state)
+                        )
+                    ),
+                    html::li::class(
+                        matches!(self, Self::History).then(|| "active"),
+                        html::a(
+                            route::line::History::href(line, state),
+                            i18n::term::line::subpage::history(state)
+                        )
+                    ),
+                    html::li::class(
+                        matches!(self, Self::Sources).then(|| "active"),
+                        html::a(
+                            route::line::Sources::href(line, state),
+                            i18n::term::line::subpage::sources(state)
+                        )
+                    ),
+                    html::li::class(
+                        matches!(self, Self::Map).then(|| "active"),
+                        html::a(
+                            route::line::Map::href(line, state),
+                            i18n::term::line::subpage::map(state)
+                        )
+                    ),
+                ))
+            )
+        )
+    }
+}
@@ -0,0 +1,505 @@
+//! The state of a line at a given date.
+
+use std::fmt;
+use htmlfn::html;
+use htmlfn::core::Content;
+use htmlfn::html::attr;
+use htmlfn::utils::{display, iter, join};
+use raildata::document::{line, point};
+use raildata::types::date::{Date, EventDate};
+use crate::{event, i18n};
+use crate::event::Cited;
+use crate::page::snip;
+use crate::state::RequestState;
+use crate::page::snip::source::Footnotes;
+use super::property;
+
+
+//------------ QueryDate -----------------------------------------------------
+
+/// A date given via the `date` query parameter.
+///
+/// The date has to be given in ISO 8601 format, i.e., `YYYY-MM-DD`.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct QueryDate {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+impl QueryDate {
+    /// Returns the date from the query of the request if present and valid.
+    pub fn from_query(state: &RequestState) -> Option<Self> {
+        Self::parse(state.query().get_first("date")?)
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.trim().splitn(3, '-');
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        if day < 1 || day > days_in_month(year, month)? {
+            return None
+        }
+        Some(QueryDate { year, month, day })
+    }
+
+    /// Returns whether a date lies on or before this date.
+    ///
+    /// Partial dates are considered to be at the beginning of their period,
+    /// so an event in “1935” is considered to have happened by
+    /// 1935-06-01.
+    pub fn includes(self, date: &Date) -> bool {
+        let date = QueryDate {
+            year: i32::from(date.year()),
+            month: date.month().unwrap_or(1),
+            day: date.day().unwrap_or(1),
+        };
+        date <= self
+    }
+
+    /// Returns whether an event date lies on or before this date.
+    ///
+    /// If the event has multiple dates, the first one is used. Events
+    /// without a date are never included.
+    pub fn includes_event(self, date: &EventDate) -> bool {
+        date.iter().next().map(|date| self.includes(date)).unwrap_or(false)
+    }
+}
+
+impl fmt::Display for QueryDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Returns the number of days of a month in the Gregorian calendar.
+///
+/// Returns `None` if `month` isn’t a valid month.
+fn days_in_month(year: i32, month: u8) -> Option<u8> {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => Some(31),
+        4 | 6 | 9 | 11 => Some(30),
+        2 => {
+            let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+            Some(if leap { 29 } else { 28 })
+        }
+        _ => None
+    }
+}
+
+
+//------------ PointState ----------------------------------------------------
+
+/// The state of a point at a given date.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PointState {
+    /// The point did not exist yet.
+    Future,
+
+    /// The point existed.
+    Open,
+
+    /// The point had been closed.
+    Closed,
+}
+
+impl PointState {
+    /// Returns the state of a point at the given date.
+    ///
+    /// The status set by the last event before the date decides. If the
+    /// point has status events but none before the date, it didn’t exist
+    /// yet. If it has no status events at all, it is assumed to have
+    /// always been open.
+    pub fn at(point: point::Document, date: QueryDate) -> Self {
+        use point::Status::*;
+
+        let events = event::sorted_events(
+            point.data().events.iter().filter(|event| {
+                event.status.is_some()
+            }),
+            |event| &event.date
+        );
+        if events.is_empty() {
+            return PointState::Open
+        }
+        let status = events.into_iter().filter(|event| {
+            date.includes_event(&event.date)
+        }).filter_map(|event| {
+            event.status.as_ref().map(|status| status.to_value())
+        }).last();
+        match status {
+            None | Some(Planned) | Some(Construction) => PointState::Future,
+            Some(Open) | Some(Reopened) | Some(Suspended) => PointState::Open,
+            Some(Closed) => PointState::Closed,
+        }
+    }
+
+    /// Returns the class for the point’s row in the route table.
+    pub fn class(self) -> &'static str {
+        match self {
+            PointState::Future => "future",
+            PointState::Open => "",
+            PointState::Closed => "closed",
+        }
+    }
+}
+
+
+//------------ Segments ------------------------------------------------------
+
+/// The line broken up into segments between consecutive points.
+///
+/// If no date is given, all events are considered, i.e., the segments
+/// describe the current state of the line.
+pub struct Segments<'a> {
+    line: line::Document<'a>,
+    points: Vec<point::Document<'a>>,
+    date: Option<QueryDate>,
+}
+
+impl<'a> Segments<'a> {
+    pub fn new(
+        line: line::Document<'a>,
+        date: Option<QueryDate>,
+        state: &'a RequestState
+    ) -> Self {
+        Segments {
+            line,
+            points: line.data().points.iter_documents(state.store()).collect(),
+            date
+        }
+    }
+
+    /// Returns the index of a point on the line.
+    fn index(&self, point: point::Document) -> Option<usize> {
+        let link = point.link();
+        self.points.iter().position(|item| item.link() == link)
+    }
+
+    /// Returns the points of the line in order.
+    pub fn points(&self) -> &[point::Document<'a>] {
+        &self.points
+    }
+
+    /// Returns the number of segments.
+    pub fn count(&self) -> usize {
+        self.points.len().saturating_sub(1)
+    }
+
+    /// Returns the ranges of segments affected by an event.
+    ///
+    /// If the event doesn’t list any sections, it affects the entire line.
+    /// Sections with points not on the line are skipped.
+    pub fn affected(
+        &self, event: &line::Event, state: &RequestState
+    ) -> Vec<(usize, usize)> {
+        if event.sections.is_empty() {
+            return vec![(0, self.count())]
+        }
+        event.sections.iter().filter_map(|section| {
+            let start = self.index(
+                section.start_point(self.line.data(), state.store())
+            )?;
+            let end = self.index(
+                section.end_point(self.line.data(), state.store())
+            )?;
+            Some((start.min(end), start.max(end)))
+        }).collect()
+    }
+
+    /// Returns the events defining a property at the date.
+    ///
+    /// The closure `has_property` selects the events that change the
+    /// property. Events are applied in the order of their dates. Returns a
+    /// list of the start index, end index, and event for each run of
+    /// consecutive segments where the property was last set by the same
+    /// event. Runs where the property wasn’t set at all are left out.
+    fn property(
+        &self,
+        has_property: impl Fn(&line::Event) -> bool,
+        state: &'a RequestState,
+    ) -> Vec<(usize, usize, &'a line::Event)> {
+        let mut segments: Vec<Option<&'a line::Event>> = vec![
+            None; self.count()
+        ];
+        let events = event::sorted_events(
+            self.line.data().events.iter(), |event| &event.date
+        );
+        for event in events {
+            let included = self.date.map(|date| {
+                date.includes_event(&event.date)
+            }).unwrap_or(true);
+            if !included || !has_property(event) {
+                continue
+            }
+            for (start, end) in self.affected(event, state) {
+                segments[start..end].iter_mut().for_each(|item| {
+                    *item = Some(event)
+                });
+            }
+        }
+
+        let mut res: Vec<(usize, usize, &'a line::Event)> = Vec::new();
+        for (idx, event) in segments.into_iter().enumerate() {
+            let event = match event {
+                Some(event) => event,
+                None => continue
+            };
+            if let Some(last) = res.last_mut() {
+                if last.1 == idx && std::ptr::eq(last.2, event) {
+                    last.1 = idx + 1;
+                    continue
+                }
+            }
+            res.push((idx, idx + 1, event));
+        }
+        res
+    }
+}
+
+
+//------------ current_events ------------------------------------------------
+
+/// Returns the events that set the current value of a property.
+///
+/// These are the events last changing the property on any part of the
+/// line in the order they appear in the line’s events.
+pub fn current_events<'a>(
+    line: line::Document<'a>,
+    has_property: impl Fn(&line::Event) -> bool,
+    state: &'a RequestState,
+) -> Vec<&'a line::Event> {
+    let runs = Segments::new(line, None, state).property(has_property, state);
+    line.data().events.iter().filter(|event| {
+        runs.iter().any(|run| std::ptr::eq(run.2, *event))
+    }).collect()
+}
+
+
+//------------ at_date -------------------------------------------------------
+
+/// The state of the line at the given date.
+pub fn at_date<'a>(
+    line: line::Document<'a>,
+    date: QueryDate,
+    notes: &Footnotes<'a>,
+    state: &'a RequestState
+) -> impl Content + 'a {
+    let segments = Segments::new(line, Some(date), state);
+    let status = segments.property(|event| event.status.is_some(), state);
+    let category = segments.property(|event| {
+        event.category.is_some()
+    }, state);
+    let gauge = segments.property(|event| event.gauge.is_some(), state);
+    let tracks = segments.property(|event| event.tracks.is_some(), state);
+    let electrified = segments.property(|event| {
+        event.electrified.is_some()
+    }, state);
+    let owner = segments.property(|event| event.owner.is_some(), state);
+    let operator = segments.property(|event| {
+        event.operator.is_some()
+    }, state);
+
+    (
+        html::h2((
+            i18n::term::line::at_date(state), " ",
+            display(date),
+        )),
+        html::dl::class("line-current", (
+            // Status
+            value(&segments, notes, status, state, |event| {
+                event.status.map(|status| property::status(status, state))
+            }).map(|dd| {(
+                html::dt(i18n::term::line::property::status(state)), dd
+            )}),
+
+            // Category
+            value(&segments, notes, category, state, |event| {
+                event.category.as_ref().map(|cat| {
+                    join(" ", cat.iter().map(|item| {
+                        property::category(*item, state)
+                    }))
+                })
+            }).map(|dd| {(
+                html::dt(i18n::term::line::property::category(state)), dd
+            )}),
+
+            // Gauge
+            value(&segments, notes, gauge, state, |event| {
+                event.gauge.as_ref().map(|gauge| {
+                    join("/", gauge.iter().map(|gauge| (
+                        display(gauge.gauge()),
+                        "\u{202f}mm"
+                    )))
+                })
+            }).map(|dd| {(
+                html::dt(i18n::term::line::property::gauge(state)), dd
+            )}),
+
+            // Tracks
+            value(&segments, notes, tracks, state, |event| {
+                event.tracks.as_ref().map(|tracks| display(tracks))
+            }).map(|dd| {(
+                html::dt(i18n::term::line::property::tracks(state)), dd
+            )}),
+
+            // Electrified
+            value(&segments, notes, electrified, state, |event| {
+                event.electrified.as_ref().map(|el| {(
+                    el.as_ref().map(|el| {
+                        join(", ", el.iter().map(|el| {
+                            property::electrified(el, state)
+                        }))
+                    }),
+                    el.is_none().then(|| i18n::term::unknown(state))
+                )})
+            }).map(|dd| {(
+                html::dt(i18n::term::line::property::electrified(state)), dd
+            )}),
+
+            // Owner
+            value(&segments, notes, owner, state, |event| {
+                event.owner.as_ref().map(|owner| {
+                    property::entities(
+                        owner.as_ref().map(|owner| owner.iter()), state
+                    )
+                })
+            }).map(|dd| {(
+                html::dt(i18n::term::line::property::owner(state)), dd
+            )}),
+
+            // Operator
+            value(&segments, notes, operator, state, |event| {
+                event.operator.as_ref().map(|operator| {
+                    property::entities(
+                        operator.as_ref().map(|operator| operator.iter()),
+                        state
+                    )
+                })
+            }).map(|dd| {(
+                html::dt(i18n::term::line::property::operator(state)), dd
+            )}),
+        ))
+    )
+}
+
+/// Renders the value of a property for all its runs.
+///
+/// Returns `None` if the property wasn’t set anywhere on the line. If a
+/// single run covers the entire line, only the value is shown. Otherwise
+/// the value is given per section as in the current state.
+fn value<'a, F, R>(
+    segments: &Segments<'a>,
+    notes: &Footnotes<'a>,
+    runs: Vec<(usize, usize, &'a line::Event)>,
+    state: &'a RequestState,
+    op: F
+) -> Option<impl Content + 'a>
+where
+    F: Fn(&'a line::Event) -> Option<R> + 'a,
+    R: Content + 'a
+{
+    if runs.is_empty() {
+        return None
+    }
+    let whole = runs.len() == 1 && runs[0].0 == 0
+        && runs[0].1 + 1 == segments.points.len();
+    let jurisdiction = segments.line.data().jurisdiction();
+    let points = segments.points.clone();
+    let refs: Vec<_> = runs.iter().map(|run| {
+        notes.refs(run.2.sources(), state)
+    }).collect();
+    Some(html::dd(
+        if whole {
+            (Some((op(runs[0].2), refs.into_iter().next())), None)
+        }
+        else {
+            (None, Some(
+                html::dl::class("line-current-sections",
+                    iter(runs.into_iter().zip(refs).map(
+                        move |((start, end, event), refs)| {(
+                            html::dt((
+                                snip::point::link(
+                                    points[start], jurisdiction, state
+                                ),
+                                " – ",
+                                snip::point::link(
+                                    points[end], jurisdiction, state
+                                ),
+                            )),
+                            html::dd((op(event), refs))
+                        )}
+                    ))
+                )
+            ))
+        }
+    ))
+}
+
+
+//------------ date_form -----------------------------------------------------
+
+/// The form for selecting a date.
+///
+/// The form submits to the current page, so it can be used on all chapters
+/// that support a date.
+pub fn date_form<'a>(
+    date: Option<QueryDate>,
+    state: &'a RequestState
+) -> impl Content + 'a {
+    html::form(
+        (
+            attr::class("line-date-form"),
+            attr::method("get"),
+        ),
+        (
+            html::span::class("line-date-form-label",
+                i18n::term::line::date_form::label(state)
+            ),
+            html::input((
+                attr::name("date"),
+                attr::type_("date"),
+                attr::placeholder("YYYY-MM-DD"),
+                date.map(|date| attr::value(date.to_string())),
+            )),
+            html::button(
+                "submit", (), i18n::term::line::date_form::submit(state)
+            ),
+            date.map(|_| {
+                html::a("?", i18n::term::line::date_form::reset(state))
+            }),
+        )
+    )
+}
+
+
+//------------ Tests ---------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_query_date() {
+        assert_eq!(
+            QueryDate::parse(" 1891-04-01 "),
+            Some(QueryDate { year: 1891, month: 4, day: 1 })
+        );
+        assert_eq!(QueryDate::parse("1891-4"), None);
+        assert_eq!(QueryDate::parse("1891-13-01"), None);
+        assert_eq!(QueryDate::parse("1891-00-01"), None);
+        assert_eq!(QueryDate::parse("1891-04-00"), None);
+        assert_eq!(QueryDate::parse("1891-04-31"), None);
+        assert_eq!(QueryDate::parse("1891-02-31"), None);
+        assert_eq!(QueryDate::parse("foo"), None);
+    }
+
+    #[test]
+    fn parse_query_date_leap_years() {
+        assert!(QueryDate::parse("1904-02-29").is_some());
+        assert!(QueryDate::parse("2000-02-29").is_some());
+        assert!(QueryDate::parse("1900-02-29").is_none());
+        assert!(QueryDate::parse("1901-02-29").is_none());
+    }
+}
@@ -0,0 +1,198 @@
+//! The detailed route of a line.
+//!
+//! Other than the route table, which shows the current state of each
+//! point, this lists all historic locations and categories of the points
+//! with their validity as well as when each point was opened and closed.
+
+use htmlfn::html;
+use htmlfn::core::Content;
+use htmlfn::utils::{iter, join};
+use raildata::document::{line, point};
+use raildata::document::combined::LineLink;
+use raildata::types::date::{Date, EventDate};
+use crate::{event, i18n};
+use crate::page::{frame, snip};
+use crate::route::Href;
+use crate::state::RequestState;
+use super::components::Chapter;
+
+const CHAPTER: Chapter = Chapter::Route;
+
+pub fn page<'a>(
+    line: line::Document<'a>, state: &'a RequestState
+) -> impl frame::Page + 'a {
+    frame::standard(state, CHAPTER.title(line, state), (), (),
+        (
+            CHAPTER.headline(line, state),
+            route(line, state),
+        )
+    )
+}
+
+
+//------------ route ---------------------------------------------------------
+
+pub fn route<'a>(
+    line: line::Document<'a>, state: &'a RequestState
+) -> impl Content + 'a {
+    let link = line.link();
+    let jurisdiction = line.data().jurisdiction();
+    (
+        html::h2(i18n::term::line::detailed_route(state)),
+        html::table::class("line-route line-route-detailed", (
+            html::tr((
+                html::th(i18n::term::line::detailed_route::location(state)),
+                html::th(i18n::term::line::detailed_route::category(state)),
+                html::th(i18n::term::line::detailed_route::name(state)),
+                html::th(i18n::term::line::detailed_route::opened(state)),
+                html::th(i18n::term::line::detailed_route::closed(state)),
+            )),
+            iter(
+                line.data().points.iter_documents(
+                    state.store()
+                ).map(move |point| {
+                    html::tr::class(
+                        if point.data().is_open() { "" }
+                        else { "closed" },
+                        (
+                            // location
+                            html::td(values(
+                                locations(point, link), state,
+                                |location| location.unwrap_or("–")
+                            )),
+
+                            // category
+                            html::td(values(
+                                categories(point), state,
+                                |cat| join(" ", cat.iter().map(|cat| {
+                                    cat.code()
+                                }))
+                            )),
+
+                            // name
+                            html::td::id(point.key().as_str(),
+                                html::a(
+                                    point.href(state),
+                                    point.data().name_in_jurisdiction(
+                                        jurisdiction
+                                    )
+                                )
+                            ),
+
+                            // opened
+                            html::td(dates(
+                                status_dates(point, |status| {
+                                    matches!(
+                                        status,
+                                        point::Status::Open
+                                        | point::Status::Reopened
+                                    )
+                                }),
+                                state
+                            )),
+
+                            // closed
+                            html::td(dates(
+                                status_dates(point, |status| {
+                                    matches!(status, point::Status::Closed)
+                                }),
+                                state
+                            )),
+                        )
+                    )
+                })
+            ),
+        ))
+    )
+}
+
+
+//------------ Historic values -----------------------------------------------
+
+/// Returns the locations of a point on a line with the event dates.
+fn locations(
+    point: point::Document, line: LineLink
+) -> Vec<(&EventDate, Option<&str>)> {
+    let events = event::sorted_events(
+        point.data().events.iter(), |event| &event.date
+    );
+    events.into_iter().filter_map(|event| {
+        let location = event.location.as_ref()?;
+        location.iter().find(|(item, _)| *item == line).map(|(_, value)| {
+            (&event.date, value)
+        })
+    }).collect()
+}
+
+/// Returns the categories of a point with the event dates.
+fn categories(
+    point: point::Document
+) -> Vec<(&EventDate, Vec<point::Category>)> {
+    let events = event::sorted_events(
+        point.data().events.iter(), |event| &event.date
+    );
+    events.into_iter().filter_map(|event| {
+        event.category.as_ref().map(|category| {(
+            &event.date,
+            category.iter().map(|cat| cat.into_value()).collect()
+        )})
+    }).collect()
+}
+
+/// Returns the dates of status changes matching a condition.
+fn status_dates(
+    point: point::Document, op: impl Fn(point::Status) -> bool
+) -> Vec<&EventDate> {
+    let events = event::sorted_events(
+        point.data().events.iter(), |event| &event.date
+    );
+    events.into_iter().filter(|event| {
+        event.status.as_ref().map(|status| {
+            op(status.to_value())
+        }).unwrap_or(false)
+    }).map(|event| &event.date).collect()
+}
+
+/// Renders a list of historic values with their validity.
+///
+/// Each value is valid from the date of its event until the date of the
+/// next event, so the values need to be sorted by date. The events of a
+/// point aren’t necessarily in that order, which is why the functions
+/// above sort them first.
+fn values<'a, T: 'a, F, R>(
+    values: Vec<(&'a EventDate, T)>,
+    state: &'a RequestState,
+    op: F,
+) -> impl Content + 'a
+where
+    F: Fn(T) -> R + 'a,
+    R: Content + 'a,
+{
+    let ends: Vec<Option<&'a Date>> = values.iter().skip(1).map(|(date, _)| {
+        date.iter().next()
+    }).chain(Some(None)).collect();
+    html::ul(
+        iter(values.into_iter().zip(ends).map(move |((start, value), end)| {
+            html::li((
+                op(value),
+                " ",
+                html::span::class("line-route-validity", (
+                    "(",
+                    snip::date::range(start.iter().next(), end, state),
+                    ")",
+                ))
+            ))
+        }))
+    )
+}
+
+/// Renders a list of event dates.
+fn dates<'a>(
+    dates: Vec<&'a EventDate>, state: &'a RequestState
+) -> impl Content + 'a {
+    html::ul(
+        iter(dates.into_iter().map(move |date| {
+            html::li(snip::date::event_date(date, state))
+        }))
+    )
+}
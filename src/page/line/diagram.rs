@@ -0,0 +1,217 @@
+//! The schematic route diagram of a line.
+//!
+//! The diagram shows the line as a vertical strip with its points in
+//! order. Each point gets a marker according to its category, its name,
+//! and its location at the right edge. Junctions get a stub labelled with
+//! the codes of the connecting lines. Closed points and sections are drawn
+//! in grey and dashed, respectively.
+
+use raildata::document::{line, point};
+use crate::json;
+use crate::route::Href;
+use crate::state::RequestState;
+use crate::svg::Svg;
+
+
+//------------ Layout --------------------------------------------------------
+
+const WIDTH: f64 = 640.;
+const MARGIN: f64 = 20.;
+const ROW: f64 = 22.;
+
+/// The x position of the right edge of the connecting line codes.
+///
+/// The codes are placed right before the start of the junction stub.
+const CONNECTION_X: f64 = STUB_X - 4.;
+
+/// The x position of the start of the junction stubs.
+const STUB_X: f64 = 108.;
+
+/// The x position of the line itself.
+const LINE_X: f64 = 130.;
+
+/// The x position of the point names.
+const NAME_X: f64 = 146.;
+
+/// The x position of the right edge of the location column.
+const LOCATION_X: f64 = WIDTH - MARGIN;
+
+/// The class of the root element.
+///
+/// Since the diagram is inlined into the route page, all rules of the
+/// style sheet are scoped by it.
+const CLASS: &str = "route-diagram";
+
+const STYLE: &str = "\
+    .route-diagram text { font: 12px sans-serif; fill: #2e3436; }\
+    .route-diagram .location { text-anchor: end; fill: #555; }\
+    .route-diagram .connection { \
+        text-anchor: end; font-size: 10px; fill: #555; \
+    }\
+    .route-diagram .section { stroke: #1d3053; stroke-width: 4; }\
+    .route-diagram .section.closed { \
+        stroke: #aaa; stroke-dasharray: 6 4; \
+    }\
+    .route-diagram .stub { stroke: #1d3053; stroke-width: 2; }\
+    .route-diagram .marker { \
+        fill: #fff; stroke: #1d3053; stroke-width: 2; \
+    }\
+    .route-diagram .marker.closed { stroke: #aaa; }\
+    .route-diagram .closed-name { fill: #aaa; }\
+";
+
+
+//------------ svg -----------------------------------------------------------
+
+/// Draws the route diagram of a line.
+pub fn svg(line: line::Document, state: &RequestState) -> Svg {
+    let link = line.link();
+    let jurisdiction = line.data().jurisdiction();
+    let points = line.data().points.iter_documents(
+        state.store()
+    ).collect::<Vec<_>>();
+    let open = open_segments(line, &points, state);
+
+    let mut svg = Svg::new(
+        WIDTH, 2. * MARGIN + ROW * points.len().max(1) as f64
+    );
+    svg.set_class(CLASS);
+    svg.style(STYLE);
+
+    // Sections first so the markers are drawn on top.
+    for (idx, open) in open.iter().enumerate() {
+        svg.line(
+            (LINE_X, y(idx)), (LINE_X, y(idx + 1)),
+            if *open { "section" } else { "section closed" }
+        );
+    }
+
+    for (idx, point) in points.iter().enumerate() {
+        let point = *point;
+        let y = y(idx);
+        let closed = !point.data().is_open();
+
+        if let Some((location, _)) = point.data().line_location(link) {
+            svg.text(
+                (LOCATION_X, y + 4.), 0., "location", &location.to_string()
+            );
+        }
+
+        if point.meta().junction {
+            let codes = point.xrefs().lines.iter().filter(|other| {
+                **other != link
+            }).map(|other| {
+                other.document(state.store()).data().code().as_str()
+            }).collect::<Vec<_>>();
+            if !codes.is_empty() {
+                svg.line((STUB_X, y - 6.), (LINE_X, y), "stub");
+                svg.text(
+                    (CONNECTION_X, y - 2.), 0., "connection",
+                    &codes.join(", ")
+                );
+            }
+        }
+
+        svg.start_link(&json::attr(point.href(state)));
+        marker(&mut svg, y, kind(point), closed);
+        svg.text(
+            (NAME_X, y + 4.), 0., if closed { "closed-name" } else { "name" },
+            point.data().name_in_jurisdiction(jurisdiction)
+        );
+        svg.end_link();
+    }
+    svg
+}
+
+/// Returns the vertical position of the point with the given index.
+fn y(idx: usize) -> f64 {
+    MARGIN + ROW * (idx as f64 + 0.5)
+}
+
+/// Returns for each segment between two points whether it is open.
+///
+/// This uses the current status of the line. Segments without a status
+/// are considered open.
+fn open_segments(
+    line: line::Document,
+    points: &[point::Document],
+    state: &RequestState
+) -> Vec<bool> {
+    use raildata::document::line::Status::*;
+
+    let mut res = vec![true; points.len().saturating_sub(1)];
+    let index = |point: point::Document| {
+        let link = point.link();
+        points.iter().position(|item| item.link() == link)
+    };
+    for (section, status) in line.data().current.status.as_slice() {
+        if matches!(status, Open | Reopened) {
+            continue
+        }
+        let start = index(section.start_point(line.data(), state.store()));
+        let end = index(section.end_point(line.data(), state.store()));
+        if let (Some(start), Some(end)) = (start, end) {
+            let (start, end) = (start.min(end), start.max(end));
+            res[start..end].iter_mut().for_each(|item| *item = false);
+        }
+    }
+    res
+}
+
+
+//------------ Kind ----------------------------------------------------------
+
+/// The kind of marker to draw for a point.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Kind {
+    /// A station where trains can stop and cross.
+    Station,
+
+    /// A stop without tracks to cross.
+    Stop,
+
+    /// A junction without passenger service.
+    Junction,
+
+    /// Anything else.
+    Other,
+}
+
+/// Determines the kind of a point from its current categories.
+///
+/// If a point has multiple categories, the most important one wins.
+fn kind(point: point::Document) -> Kind {
+    use raildata::document::point::Category::*;
+
+    let mut res = Kind::Other;
+    let cats = match point.data().category() {
+        Some((cats, _)) => cats,
+        None => return res
+    };
+    for cat in cats {
+        let kind = match cat {
+            DeBf | DeKrbf | GbSt | DkSt | NlSt | NoS => Kind::Station,
+            DeHp | DeHst | GbHalt | DkT | NlH | NoHp => Kind::Stop,
+            DeAbzw | DeUest | DeUehst | GbJn => Kind::Junction,
+            _ => Kind::Other,
+        };
+        res = match (res, kind) {
+            (Kind::Station, _) | (_, Kind::Station) => Kind::Station,
+            (Kind::Stop, _) | (_, Kind::Stop) => Kind::Stop,
+            (Kind::Junction, _) | (_, Kind::Junction) => Kind::Junction,
+            _ => Kind::Other,
+        };
+    }
+    res
+}
+
+/// Draws the marker for a point.
+fn marker(svg: &mut Svg, y: f64, kind: Kind, closed: bool) {
+    let class = if closed { "marker closed" } else { "marker" };
+    match kind {
+        Kind::Station => svg.rect((LINE_X - 7., y - 5.), (14., 10.), class),
+        Kind::Stop => svg.circle((LINE_X, y), 5., class),
+        Kind::Junction => svg.circle((LINE_X, y), 3., class),
+        Kind::Other => svg.line((LINE_X - 5., y), (LINE_X + 5., y), class),
+    }
+}
@@ -0,0 +1,178 @@
+use htmlfn::html;
+use htmlfn::core::Content;
+use htmlfn::utils::{display, either, iter, join};
+use raildata::document::line;
+use crate::{event, i18n};
+use crate::event::Cited;
+use crate::page::{frame, snip};
+use crate::state::RequestState;
+use super::components::Chapter;
+use super::{property, sources};
+
+
+const CHAPTER: Chapter = Chapter::History;
+
+pub fn page<'a>(
+    line: line::Document<'a>, state: &'a RequestState
+) -> impl frame::Page + 'a {
+    frame::standard(state, CHAPTER.title(line, state), (), (),
+        (
+            CHAPTER.headline(line, state),
+            history(line, state),
+        )
+    )
+}
+
+
+//------------ history -------------------------------------------------------
+
+pub fn history<'a>(
+    line: line::Document<'a>, state: &'a RequestState
+) -> impl Content + 'a {
+    let notes = sources::footnotes(line, state);
+    let events = event::sorted_events(
+        line.data().events.iter(), |event| &event.date
+    );
+    let refs: Vec<_> = events.iter().map(|event| {
+        notes.refs(event.sources(), state)
+    }).collect();
+    (
+        html::h2(i18n::term::line::history(state)),
+        either(events.is_empty(),
+            || html::p(i18n::term::line::history::empty(state)),
+            || html::ol::class("line-history",
+                iter(events.into_iter().zip(refs).map(
+                    move |(event, refs)| item(line, event, refs, state)
+                ))
+            )
+        ),
+        notes.list(state),
+    )
+}
+
+fn item<'a>(
+    line: line::Document<'a>,
+    event: &'a line::Event,
+    refs: impl Content + 'a,
+    state: &'a RequestState
+) -> impl Content + 'a {
+    html::li((
+        html::p::class("line-history-date", (
+            snip::date::event_date(&event.date, state),
+            refs,
+        )),
+        html::p::class("line-history-sections", sections(line, event, state)),
+        html::dl::class("line-history-properties", (
+            // Name
+            event.name.as_ref().map(|name| {(
+                html::dt(i18n::term::line::property::name(state)),
+                html::dd(
+                    name.as_ref().and_then(|name| {
+                        name.for_language(state.lang().into())
+                    }).unwrap_or("–")
+                )
+            )}),
+
+            // Status
+            event.status.map(|status| {(
+                html::dt(i18n::term::line::property::status(state)),
+                html::dd(property::status(status, state))
+            )}),
+
+            // Category
+            event.category.as_ref().map(|cat| {(
+                html::dt(i18n::term::line::property::category(state)),
+                html::dd(join(" ", cat.iter().map(|item| {
+                    property::category(*item, state)
+                })))
+            )}),
+
+            // Gauge
+            event.gauge.as_ref().map(|gauge| {(
+                html::dt(i18n::term::line::property::gauge(state)),
+                html::dd(join("/", gauge.iter().map(|gauge| (
+                    display(gauge.gauge()),
+                    "\u{202f}mm"
+                ))))
+            )}),
+
+            // Rails
+            event.rails.as_ref().map(|rails| {(
+                html::dt(i18n::term::line::property::rails(state)),
+                html::dd(display(rails))
+            )}),
+
+            // Tracks
+            event.tracks.as_ref().map(|tracks| {(
+                html::dt(i18n::term::line::property::tracks(state)),
+                html::dd(display(tracks))
+            )}),
+
+            // Electrified
+            event.electrified.as_ref().map(|el| {(
+                html::dt(i18n::term::line::property::electrified(state)),
+                html::dd((
+                    el.as_ref().map(|el| {
+                        join(", ", el.iter().map(|el| {
+                            property::electrified(el, state)
+                        }))
+                    }),
+                    el.is_none().then(|| i18n::term::unknown(state))
+                ))
+            )}),
+
+            // Passenger
+            event.passenger.map(|service| {(
+                html::dt(i18n::term::line::property::passenger(state)),
+                html::dd(property::passenger(service, state))
+            )}),
+
+            // Goods
+            event.goods.map(|service| {(
+                html::dt(i18n::term::line::property::goods(state)),
+                html::dd(property::goods(service, state))
+            )}),
+
+            // Owner
+            event.owner.as_ref().map(|owner| {(
+                html::dt(i18n::term::line::property::owner(state)),
+                html::dd(property::entities(
+                    owner.as_ref().map(|owner| owner.iter()), state
+                ))
+            )}),
+
+            // Operator
+            event.operator.as_ref().map(|operator| {(
+                html::dt(i18n::term::line::property::operator(state)),
+                html::dd(property::entities(
+                    operator.as_ref().map(|operator| operator.iter()), state
+                ))
+            )}),
+        )),
+    ))
+}
+
+/// The sections of the line affected by an event.
+///
+/// If the event doesn’t list any sections, it affects the entire line.
+fn sections<'a>(
+    line: line::Document<'a>,
+    event: &'a line::Event,
+    state: &'a RequestState
+) -> impl Content + 'a {
+    let jurisdiction = line.data().jurisdiction();
+    either(event.sections.is_empty(),
+        || i18n::term::line::history::entire_line(state),
+        move || join(", ", event.sections.iter().map(move |section| {(
+            snip::point::link(
+                section.start_point(line.data(), state.store()),
+                jurisdiction, state
+            ),
+            " – ",
+            snip::point::link(
+                section.end_point(line.data(), state.store()),
+                jurisdiction, state
+            ),
+        )}))
+    )
+}
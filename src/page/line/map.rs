@@ -0,0 +1,100 @@
+use htmlfn::html;
+use htmlfn::html::attr;
+use raildata::document::line;
+use crate::{geo, i18n, route};
+use crate::geo::Projection;
+use crate::json;
+use crate::page::frame;
+use crate::route::Href;
+use crate::route::document::Format;
+use crate::state::RequestState;
+use crate::svg::Svg;
+use super::components::Chapter;
+
+const CHAPTER: Chapter = Chapter::Map;
+
+pub fn page<'a>(
+    line: line::Document<'a>, state: &'a RequestState
+) -> impl frame::Page + 'a {
+    frame::standard(state, CHAPTER.title(line, state), (), (),
+        (
+            CHAPTER.headline(line, state),
+            html::p::class("line-map",
+                html::img::attrs((
+                    attr::src(route::line::MapSvg::href(line, state)),
+                    attr::alt(i18n::term::line::map::alt(state)),
+                ))
+            ),
+            html::ul::class("line-map-downloads", (
+                html::li(html::a(
+                    Format::GeoJson.href(line.link(), state),
+                    i18n::term::line::map::geojson(state)
+                )),
+                html::li(html::a(
+                    route::line::MapSvg::href(line, state),
+                    i18n::term::line::map::svg(state)
+                )),
+            )),
+        )
+    )
+}
+
+
+//------------ svg -----------------------------------------------------------
+
+/// The size of the map.
+const WIDTH: f64 = 800.;
+const HEIGHT: f64 = 600.;
+const MARGIN: f64 = 40.;
+
+const STYLE: &str = "\
+    .course { fill: none; stroke: #1d3053; stroke-width: 3; }\
+    .point { fill: #fff; stroke: #1d3053; stroke-width: 2; }\
+    .point.closed { stroke: #aaa; }\
+    .label { font: 11px sans-serif; fill: #2e3436; }\
+";
+
+/// Draws the course of the line and its points as an outline map.
+pub fn svg(line: line::Document, state: &RequestState) -> Svg {
+    let course = geo::line_course(line, state.store());
+    let points = line.data().points.iter_documents(
+        state.store()
+    ).filter_map(|point| {
+        geo::point_coord(point, state.store()).map(|coord| (point, coord))
+    }).collect::<Vec<_>>();
+
+    let mut svg = Svg::new(WIDTH, HEIGHT);
+    svg.style(STYLE);
+    let projection = Projection::fit(
+        course.iter().flatten().chain(points.iter().map(|(_, coord)| coord)),
+        WIDTH, HEIGHT, MARGIN
+    );
+    let projection = match projection {
+        Some(projection) => projection,
+        None => return svg
+    };
+
+    for coords in &course {
+        svg.polyline(
+            coords.iter().map(|coord| projection.project(*coord)),
+            "course"
+        );
+    }
+    let jurisdiction = line.data().jurisdiction();
+    for (point, coord) in points {
+        let pos = projection.project(coord);
+        svg.start_link(&json::attr(point.link().href(state)));
+        svg.circle(
+            pos, 4.,
+            if point.data().is_open() { "point" } else { "point closed" }
+        );
+        if point.meta().junction {
+            svg.text(
+                (pos.0 + 7., pos.1 + 4.), 0., "label",
+                point.data().name_in_jurisdiction(jurisdiction)
+            );
+        }
+        svg.end_link();
+    }
+    svg
+}
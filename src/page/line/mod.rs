@@ -1,6 +1,18 @@
+pub use self::detailed::page as detailed_route;
+pub use self::history::page as history;
+pub use self::map::page as map;
 pub use self::overview::page as overview;
+pub use self::route::page as route;
+pub use self::sources::page as sources;
 
+pub mod dated;
+pub mod detailed;
+pub mod diagram;
+pub mod history;
+pub mod map;
 pub mod overview;
+pub mod route;
+pub mod sources;
 
+mod components;
 pub mod property;
-
@@ -1,52 +1,45 @@
 use htmlfn::html;
 use htmlfn::core::Content;
 use htmlfn::utils::{display, iter, join};
-use raildata::document::line;
+use raildata::document::{Document, line};
 use crate::i18n;
-use crate::page::{frame, snip};
+use crate::event::Cited;
+use crate::page::{frame, snip, structure};
+use crate::page::snip::source::Footnotes;
 use crate::route::Href;
 use crate::state::RequestState;
-use super::property;
+use super::components::Chapter;
+use super::dated;
+use super::dated::QueryDate;
+use super::{property, sources};
 
 
+const CHAPTER: Chapter = Chapter::Overview;
+
 pub fn page<'a>(
     line: line::Document<'a>, state: &'a RequestState
 ) -> impl frame::Page + 'a {
-    frame::standard(state, snip::line::title(line, state), (), (),
+    let date = QueryDate::from_query(state);
+    let notes = sources::footnotes(line, state);
+    frame::standard(state, CHAPTER.title(line, state), (), (),
         (
-            headline(line, state),
-            current(line, state),
-            route(line, state),
+            CHAPTER.headline(line, state),
+            dated::date_form(date, state),
+            date.map(|date| dated::at_date(line, date, &notes, state)),
+            date.is_none().then(|| current(line, &notes, state)),
+            notes.list(state),
+            snip::cite::cite(Document::Line(line), state),
         )
     )
 }
 
 
-fn headline<'a>(
-    line: line::Document<'a>, state: &'a RequestState
-) -> impl Content + 'a {
-    let jurisdiction = line.data().jurisdiction();
-    html::h1::class("line-headline", (
-        html::span::class("line-headline-code", (
-            line.data().code().as_str(), ". "
-        )),
-        html::span::class("line-headline-course", (
-            line.data().points.first_junction(
-                state.store()
-            ).data().name_in_jurisdiction(jurisdiction),
-            " – ",
-            line.data().points.last_junction(
-                state.store()
-            ).data().name_in_jurisdiction(jurisdiction),
-        ))
-    ))
-}
-
-
 //------------ current -------------------------------------------------------
 
 pub fn current<'a>(
-    line: line::Document<'a>, state: &'a RequestState
+    line: line::Document<'a>,
+    notes: &Footnotes<'a>,
+    state: &'a RequestState
 ) -> impl Content + 'a {
     let jurisdiction = line.data().jurisdiction();
     (
@@ -55,153 +48,215 @@ pub fn current<'a>(
             // Name
             line.data().current.name.and_then(|name| {(
                 html::dt(i18n::term::line::property::name(state)),
-                html::dd(current_value(line, name, state, |name| {
-                    name.as_ref().and_then(|name| {
-                        name.for_language(state.lang().into())
-                    }).unwrap_or("–")
-                }))
+                html::dd((
+                    current_value(line, name, state, |name| {
+                        name.as_ref().and_then(|name| {
+                            name.for_language(state.lang().into())
+                        }).unwrap_or("–")
+                    }),
+                    current_refs(line, notes, |event| {
+                        event.name.is_some()
+                    }, state),
+                ))
             )}),
 
             // Course
             //
-            html::dt(i18n::term::line::property::course(state)),
-            html::dd(
-                join(
-                    " – ",
-                    line.data().points.iter_documents(
-                        state.store()
-                    ).filter_map(move |point| {
-                        point.meta().junction.then(|| {
-                            html::a(
-                                point.href(state),
-                                point.data().name_in_jurisdiction(jurisdiction)
-                            )
+            (
+                html::dt(i18n::term::line::property::course(state)),
+                html::dd(
+                    join(
+                        " – ",
+                        line.data().points.iter_documents(
+                            state.store()
+                        ).filter_map(move |point| {
+                            point.meta().junction.then(|| {
+                                html::a(
+                                    point.href(state),
+                                    point.data().name_in_jurisdiction(
+                                        jurisdiction
+                                    )
+                                )
+                            })
                         })
-                    })
-                )
+                    )
+                ),
             ),
 
+            // Structures
+            //
+            structure::structures(Document::Line(line), state),
+
             // Status
             //
             line.data().current.status.and_then(|status| {(
                 html::dt(i18n::term::line::property::status(state)),
-                html::dd(current_value(line, status, state, |status| {
-                    property::status(*status, state)
-                }))
+                html::dd((
+                    current_value(line, status, state, |status| {
+                        property::status(*status, state)
+                    }),
+                    current_refs(line, notes, |event| {
+                        event.status.is_some()
+                    }, state),
+                ))
             )}),
 
             // Category
             //
             line.data().current.category.and_then(|cat| {(
                 html::dt(i18n::term::line::property::category(state)),
-                html::dd(current_value(line, cat, state, |cat| {
-                    join(" ", cat.iter().map(|item| {
-                        property::category(*item, state)
-                    }))
-                }))
+                html::dd((
+                    current_value(line, cat, state, |cat| {
+                        join(" ", cat.iter().map(|item| {
+                            property::category(*item, state)
+                        }))
+                    }),
+                    current_refs(line, notes, |event| {
+                        event.category.is_some()
+                    }, state),
+                ))
             )}),
 
             // Gauge
             //
             line.data().current.gauge.and_then(|gauge| {(
                 html::dt(i18n::term::line::property::gauge(state)),
-                html::dd(current_value(line, gauge, state, |gauge| {
-                    join("/", gauge.iter().map(|gauge| (
-                        display(gauge.gauge()),
-                        "\u{202f}mm"
-                    )))
-                }))
+                html::dd((
+                    current_value(line, gauge, state, |gauge| {
+                        join("/", gauge.iter().map(|gauge| (
+                            display(gauge.gauge()),
+                            "\u{202f}mm"
+                        )))
+                    }),
+                    current_refs(line, notes, |event| {
+                        event.gauge.is_some()
+                    }, state),
+                ))
             )}),
 
             // Rails
             //
             line.data().current.rails.and_then(|rails| {(
                 html::dt(i18n::term::line::property::rails(state)),
-                html::dd(current_value(line, rails, state, |rails| {
-                    display(rails)
-                }))
+                html::dd((
+                    current_value(line, rails, state, |rails| {
+                        display(rails)
+                    }),
+                    current_refs(line, notes, |event| {
+                        event.rails.is_some()
+                    }, state),
+                ))
             )}),
 
             // Tracks
             //
             line.data().current.tracks.and_then(|tracks| {(
                 html::dt(i18n::term::line::property::tracks(state)),
-                html::dd(current_value(line, tracks, state, |tracks| {
-                    display(tracks)
-                }))
+                html::dd((
+                    current_value(line, tracks, state, |tracks| {
+                        display(tracks)
+                    }),
+                    current_refs(line, notes, |event| {
+                        event.tracks.is_some()
+                    }, state),
+                ))
             )}),
 
             // Electrified
             //
             line.data().current.electrified.and_then(|el| {(
                 html::dt(i18n::term::line::property::electrified(state)),
-                html::dd(current_value(line, el, state, |el| {(
-                    el.as_ref().map(|el| {
-                        Some(join(", ", el.iter().map(|el| {
-                            property::electrified(el, state)
-                        })))
-                    }),
-                    el.is_none().then(|| {
-                        i18n::term::unknown(state)
-                    })
-                )}))
+                html::dd((
+                    current_value(line, el, state, |el| {(
+                        el.as_ref().map(|el| {
+                            Some(join(", ", el.iter().map(|el| {
+                                property::electrified(el, state)
+                            })))
+                        }),
+                        el.is_none().then(|| {
+                            i18n::term::unknown(state)
+                        })
+                    )}),
+                    current_refs(line, notes, |event| {
+                        event.electrified.is_some()
+                    }, state),
+                ))
             )}),
 
             // Passenger
             line.data().current.passenger.and_then(|service| {(
                 html::dt(i18n::term::line::property::passenger(state)),
-                html::dd(current_value(line, service, state, |service| {
-                    property::passenger(*service, state)
-                }))
+                html::dd((
+                    current_value(line, service, state, |service| {
+                        property::passenger(*service, state)
+                    }),
+                    current_refs(line, notes, |event| {
+                        event.passenger.is_some()
+                    }, state),
+                ))
             )}),
 
             // Goods
             line.data().current.goods.and_then(|service| {(
                 html::dt(i18n::term::line::property::goods(state)),
-                html::dd(current_value(line, service, state, |service| {
-                    property::goods(*service, state)
-                }))
+                html::dd((
+                    current_value(line, service, state, |service| {
+                        property::goods(*service, state)
+                    }),
+                    current_refs(line, notes, |event| {
+                        event.goods.is_some()
+                    }, state),
+                ))
             )}),
 
             // Owner
             line.data().current.owner.and_then(|owner| {(
                 html::dt(i18n::term::line::property::owner(state)),
-                html::dd(current_value(line, owner, state, |owner| {(
-                    owner.as_ref().map(|owner| {
-                        join(", ", owner.iter().map(|owner| {
-                            snip::entity::link(
-                                owner.document(state.store()),
-                                state
-                            )
-                        }))
+                html::dd((
+                    current_value(line, owner, state, |owner| {
+                        property::entities(
+                            owner.as_ref().map(|owner| owner.iter()), state
+                        )
                     }),
-                    owner.is_none().then(|| {
-                        i18n::term::unknown(state)
-                    })
-                )}))
+                    current_refs(line, notes, |event| {
+                        event.owner.is_some()
+                    }, state),
+                ))
             )}),
 
             // Operator
             line.data().current.operator.and_then(|operator| {(
                 html::dt(i18n::term::line::property::operator(state)),
-                html::dd(current_value(line, operator, state, |operator| {(
-                    operator.as_ref().map(|operator| {
-                        join(", ", operator.iter().map(|operator| {
-                            snip::entity::link(
-                                operator.document(state.store()),
-                                state
-                            )
-                        }))
+                html::dd((
+                    current_value(line, operator, state, |operator| {
+                        property::entities(
+                            operator.as_ref().map(|operator| operator.iter()),
+                            state
+                        )
                     }),
-                    operator.is_none().then(|| {
-                        i18n::term::unknown(state)
-                    })
-                )}))
+                    current_refs(line, notes, |event| {
+                        event.operator.is_some()
+                    }, state),
+                ))
             )}),
         ))
     )
 }
 
+/// The references for the current value of a property.
+fn current_refs<'a>(
+    line: line::Document<'a>,
+    notes: &Footnotes<'a>,
+    has_property: impl Fn(&line::Event) -> bool,
+    state: &'a RequestState
+) -> impl Content + 'a {
+    notes.refs(
+        dated::current_events(line, has_property, state).into_iter()
+            .flat_map(Cited::sources),
+        state
+    )
+}
+
 fn current_value<'a, T, F, R>(
     line: line::Document<'a>,
     value: &'a line::CurrentValue<T>,
@@ -240,82 +295,3 @@ where
         ))
     }
 }
-
-
-//------------ route ---------------------------------------------------------
-
-pub fn route<'a>(
-    line: line::Document<'a>, state: &'a RequestState
-) -> impl Content + 'a {
-    let link = line.link();
-    let jurisdiction = line.data().jurisdiction();
-    (
-        html::h2(i18n::term::line::route(state)),
-        html::table::class("line-route",
-            iter(
-                line.data().points.iter_documents(
-                    state.store()
-                ).map(move |point| {
-                    html::tr::class(
-                        if point.data().is_open() { "" }
-                        else { "closed" },
-                        (
-                            // location
-                            html::td(
-                                point.data().line_location(
-                                    link
-                                ).map(|(location, changed)| {
-                                    (location, changed.then(|| "*"))
-                                })
-                            ),
-
-                            // category
-                            html::td(
-                                point.data().category().map(|(cat, changed)| {(
-                                    join(" ", cat.map(|cat| cat.code())),
-                                    changed.then(|| "*")
-                                )})
-                            ),
-
-                            // name
-                            html::td(
-                                html::a(
-                                    point.href(state),
-                                    point.data().name_in_jurisdiction(
-                                        jurisdiction
-                                    )
-                                )
-                            ),
-
-                            // connections
-                            html::td::class("line-route-connections",
-                                if point.meta().junction {
-                                    Some(html::ul(
-                                        iter(
-                                            point.xrefs().lines.iter().filter(
-                                                move |line| **line != link
-                                            ).map(|line| {
-                                                html::li(
-                                                    snip::line::link(
-                                                        line.document(
-                                                            state.store()
-                                                        ),
-                                                        state
-                                                    )
-                                                )
-                                            })
-                                        )
-                                    ))
-                                }
-                                else {
-                                    None
-                                }
-                            ),
-                        )
-                    )
-                })
-            )
-        )
-    )
-}
-
@@ -1,9 +1,11 @@
 use htmlfn::core::Content;
-use htmlfn::utils::display;
+use htmlfn::utils::{display, join};
+use raildata::document::entity;
 use raildata::document::line::{
     Category, Goods, Electrified, Passenger, Status
 };
 use crate::i18n;
+use crate::page::snip;
 use crate::state::RequestState;
 
 pub fn category(cat: Category, state: &RequestState) -> &'static str {
@@ -53,6 +55,24 @@ pub fn electrified<'a>(
     )
 }
 
+/// A list of entities such as owners or operators.
+///
+/// If the list is missing, the entities are unknown.
+pub fn entities<'a>(
+    list: Option<impl Iterator<Item = &'a entity::Link> + 'a>,
+    state: &'a RequestState
+) -> impl Content + 'a {
+    let unknown = list.is_none();
+    (
+        list.map(|list| {
+            join(", ", list.map(|item| {
+                snip::entity::link(item.document(state.store()), state)
+            }))
+        }),
+        unknown.then(|| i18n::term::unknown(state))
+    )
+}
+
 pub fn goods(goods: Goods, state: &RequestState) -> &'static str {
     use self::Goods::*;
 
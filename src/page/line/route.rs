@@ -0,0 +1,167 @@
+use htmlfn::html;
+use htmlfn::core::Content;
+use htmlfn::utils::{iter, join};
+use raildata::document::{line, point};
+use crate::{i18n, route};
+use crate::page::{frame, snip};
+use crate::route::Href;
+use crate::state::RequestState;
+use super::components::Chapter;
+use super::dated;
+use super::dated::{PointState, QueryDate};
+
+const CHAPTER: Chapter = Chapter::Route;
+
+pub fn page<'a>(
+    line: line::Document<'a>, state: &'a RequestState
+) -> impl frame::Page + 'a {
+    let date = QueryDate::from_query(state);
+    frame::standard(state, CHAPTER.title(line, state), (), (),
+        (
+            CHAPTER.headline(line, state),
+            diagram(line, state),
+            dated::date_form(date, state),
+            route(line, date, state),
+        )
+    )
+}
+
+
+//------------ diagram -------------------------------------------------------
+
+/// The schematic route diagram with a download link.
+///
+/// The diagram is inlined so that the links to the points work.
+pub fn diagram<'a>(
+    line: line::Document<'a>, state: &'a RequestState
+) -> impl Content + 'a {
+    (
+        html::h2(i18n::term::line::diagram(state)),
+        html::div::class("line-diagram", (
+            super::diagram::svg(line, state).inline(),
+            html::p(html::a(
+                route::line::DiagramSvg::href(line, state),
+                i18n::term::line::diagram::download(state)
+            )),
+        ))
+    )
+}
+
+
+//------------ route ---------------------------------------------------------
+
+/// The table of points along the line.
+///
+/// If a date is given, points are marked according to their state at that
+/// date. Otherwise closed points are marked.
+pub fn route<'a>(
+    line: line::Document<'a>,
+    date: Option<QueryDate>,
+    state: &'a RequestState
+) -> impl Content + 'a {
+    let link = line.link();
+    let jurisdiction = line.data().jurisdiction();
+    (
+        html::h2(i18n::term::line::route(state)),
+        html::p::class("line-route-detailed-link",
+            html::a(
+                route::line::DetailedRoute::href(line, state),
+                i18n::term::line::detailed_route::link(state)
+            )
+        ),
+        html::table::class("line-route",
+            iter(
+                line.data().points.iter_documents(
+                    state.store()
+                ).map(move |point| {
+                    html::tr::class(
+                        match date {
+                            Some(date) => {
+                                PointState::at(point, date).class()
+                            }
+                            None => {
+                                if point.data().is_open() { "" }
+                                else { "closed" }
+                            }
+                        },
+                        (
+                            // location
+                            html::td(
+                                point.data().line_location(
+                                    link
+                                ).map(|(location, changed)| {(
+                                    location,
+                                    changed.then(|| {
+                                        changed_marker(line, point, state)
+                                    })
+                                )})
+                            ),
+
+                            // category
+                            html::td(
+                                point.data().category().map(|(cat, changed)| {(
+                                    join(" ", cat.map(|cat| cat.code())),
+                                    changed.then(|| {
+                                        changed_marker(line, point, state)
+                                    })
+                                )})
+                            ),
+
+                            // name
+                            html::td(
+                                html::a(
+                                    point.href(state),
+                                    point.data().name_in_jurisdiction(
+                                        jurisdiction
+                                    )
+                                )
+                            ),
+
+                            // connections
+                            html::td::class("line-route-connections",
+                                if point.meta().junction {
+                                    Some(html::ul(
+                                        iter(
+                                            point.xrefs().lines.iter().filter(
+                                                move |line| **line != link
+                                            ).map(|line| {
+                                                html::li(
+                                                    snip::line::link(
+                                                        line.document(
+                                                            state.store()
+                                                        ),
+                                                        state
+                                                    )
+                                                )
+                                            })
+                                        )
+                                    ))
+                                }
+                                else {
+                                    None
+                                }
+                            ),
+                        )
+                    )
+                })
+            )
+        )
+    )
+}
+
+/// The marker for a value that has changed over time.
+///
+/// This links to the point’s row in the detailed route.
+fn changed_marker<'a>(
+    line: line::Document<'a>,
+    point: point::Document<'a>,
+    state: &'a RequestState
+) -> impl Content + 'a {
+    html::a::class("line-route-changed",
+        (
+            route::line::DetailedRoute::href(line, state),
+            "#", point.key().as_str()
+        ),
+        "*"
+    )
+}
@@ -0,0 +1,48 @@
+use htmlfn::html;
+use htmlfn::core::Content;
+use htmlfn::utils::either;
+use raildata::document::line;
+use crate::i18n;
+use crate::event::Cited;
+use crate::page::frame;
+use crate::page::snip::source::Footnotes;
+use crate::state::RequestState;
+use super::components::Chapter;
+
+const CHAPTER: Chapter = Chapter::Sources;
+
+pub fn page<'a>(
+    line: line::Document<'a>, state: &'a RequestState
+) -> impl frame::Page + 'a {
+    frame::standard(state, CHAPTER.title(line, state), (), (),
+        (
+            CHAPTER.headline(line, state),
+            sources(line, state),
+        )
+    )
+}
+
+
+//------------ sources -------------------------------------------------------
+
+pub fn sources<'a>(
+    line: line::Document<'a>, state: &'a RequestState
+) -> impl Content + 'a {
+    let notes = footnotes(line, state);
+    either(notes.is_empty(),
+        || (
+            html::h2(i18n::term::line::sources(state)),
+            html::p(i18n::term::line::sources::empty(state)),
+        ),
+        || notes.list(state)
+    )
+}
+
+/// Returns the footnotes for all sources cited by the line’s events.
+pub fn footnotes<'a>(
+    line: line::Document<'a>, state: &'a RequestState
+) -> Footnotes<'a> {
+    Footnotes::new(
+        line.data().events.iter().flat_map(Cited::sources), state
+    )
+}
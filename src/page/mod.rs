@@ -1,11 +1,13 @@
 pub use self::frame::Page;
 
 mod frame;
+pub mod admin;
 pub mod aux;
 pub mod error;
 pub mod entity;
 pub mod home;
 pub mod line;
+pub mod path;
 pub mod panels;
 pub mod point;
 pub mod region;
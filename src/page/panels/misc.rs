@@ -17,7 +17,7 @@ pub fn lang_select(state: &RequestState) -> impl Content + '_ {
             iter(Lang::all().map(|lang| {
                 html::li::class(
                     if lang == state.lang() { "active" } else { "" },
-                    html::a(("?lang=", lang.code()), (
+                    html::a(lang_href(lang, state), (
                         html::span::class("code", lang.code()),
                         html::span::class("name", lang.name()),
                     ))
@@ -27,6 +27,21 @@ pub fn lang_select(state: &RequestState) -> impl Content + '_ {
     ))
 }
 
+/// Returns the href for switching to another language.
+///
+/// Normally, the language is switched via the query. Detached pages such
+/// as the static export have a separate tree per language instead, so
+/// there the link points to the same page in the other tree.
+fn lang_href(lang: Lang, state: &RequestState) -> String {
+    match state.detached_path() {
+        Some("") => format!("{}../{}/", state.url_base(), lang.code()),
+        Some(path) => {
+            format!("{}../{}/{}/", state.url_base(), lang.code(), path)
+        }
+        None => format!("?lang={}", lang.code()),
+    }
+}
+
 //------------ search_bar ----------------------------------------------------
 
 pub fn search_bar<'a>(
@@ -0,0 +1,207 @@
+use htmlfn::html;
+use htmlfn::html::attr;
+use htmlfn::core::Content;
+use htmlfn::utils::{display, either, iter, join};
+use raildata::document::{line, path, point};
+use crate::{geo, i18n, json, route};
+use crate::geo::{Coord, Projection};
+use crate::gpx::Gpx;
+use crate::page::{frame, snip};
+use crate::route::Href;
+use crate::route::document::Format;
+use crate::state::RequestState;
+use crate::svg::Svg;
+
+pub fn overview<'a>(
+    path: path::Document<'a>, state: &'a RequestState
+) -> impl frame::Page + 'a {
+    let coords = geo::path_coords(path);
+    frame::standard(state,
+        snip::path::title(path, state), (), (),
+        (
+            html::h1(
+                snip::path::title(path, state)
+            ),
+            properties(path, &coords, state),
+            html::p::class("path-outline",
+                html::img::attrs((
+                    attr::src(route::path::OutlineSvg::href(path, state)),
+                    attr::alt(i18n::term::path::outline(state)),
+                ))
+            ),
+            html::ul::class("path-downloads", (
+                html::li(html::a(
+                    Format::GeoJson.href(path.link(), state),
+                    i18n::term::path::download::geojson(state)
+                )),
+                html::li(html::a(
+                    route::path::Gpx::href(path, state),
+                    i18n::term::path::download::gpx(state)
+                )),
+                html::li(html::a(
+                    route::path::OutlineSvg::href(path, state),
+                    i18n::term::path::download::svg(state)
+                )),
+            )),
+            nodes(path, coords, state),
+        )
+    )
+}
+
+
+//------------ properties ----------------------------------------------------
+
+pub fn properties<'a>(
+    path: path::Document<'a>, coords: &[Coord], state: &'a RequestState
+) -> impl Content + 'a {
+    let lines = lines(path, state);
+    (
+        html::h2(i18n::term::path::properties(state)),
+        html::dl::class("path-properties", (
+            // Length
+            html::dt(i18n::term::path::property::length(state)),
+            html::dd((
+                format!("{:.3}", geo::length(coords) / 1000.),
+                "\u{202f}km"
+            )),
+
+            // Nodes
+            html::dt(i18n::term::path::property::nodes(state)),
+            html::dd(display(coords.len())),
+
+            // Lines
+            html::dt(i18n::term::path::property::lines(state)),
+            html::dd(either(lines.is_empty(),
+                || "–",
+                move || join(", ", lines.into_iter().map(move |line| {
+                    snip::line::link(line, state)
+                }))
+            )),
+        ))
+    )
+}
+
+
+//------------ nodes ---------------------------------------------------------
+
+/// The table of all nodes of the path.
+///
+/// For each node, the table shows its coordinates, the distance from the
+/// start of the path, and the points located at the node.
+pub fn nodes<'a>(
+    path: path::Document<'a>, coords: Vec<Coord>, state: &'a RequestState
+) -> impl Content + 'a {
+    let points = points(path, state);
+    let mut distance = 0.;
+    let rows: Vec<_> = coords.iter().enumerate().map(|(idx, coord)| {
+        if idx > 0 {
+            distance += coords[idx - 1].distance(*coord);
+        }
+        (
+            idx, *coord, distance,
+            points.iter().filter(|(node, _)| *node == idx).map(|(_, point)| {
+                *point
+            }).collect::<Vec<_>>()
+        )
+    }).collect();
+    (
+        html::h2(i18n::term::path::nodes(state)),
+        html::table::class("path-nodes", (
+            html::tr((
+                html::th("#"),
+                html::th(i18n::term::path::node::lat(state)),
+                html::th(i18n::term::path::node::lon(state)),
+                html::th(i18n::term::path::node::distance(state)),
+                html::th(i18n::term::path::node::points(state)),
+            )),
+            iter(rows.into_iter().map(move |(idx, coord, distance, points)| {
+                html::tr((
+                    html::td(display(idx)),
+                    html::td(format!("{:.6}", coord.lat)),
+                    html::td(format!("{:.6}", coord.lon)),
+                    html::td(format!("{:.3}", distance / 1000.)),
+                    html::td(join(", ", points.into_iter().map(move |point| {
+                        snip::point::link(point, None, state)
+                    }))),
+                ))
+            })),
+        ))
+    )
+}
+
+
+//------------ lines and points ----------------------------------------------
+
+/// Returns all lines whose course uses the path.
+pub fn lines<'a>(
+    path: path::Document<'a>, state: &'a RequestState
+) -> Vec<line::Document<'a>> {
+    state.index().path_lines(path.link()).iter().map(|link| {
+        link.document(state.store())
+    }).collect()
+}
+
+/// Returns all points located on the path with the index of their node.
+pub fn points<'a>(
+    path: path::Document<'a>, state: &'a RequestState
+) -> Vec<(usize, point::Document<'a>)> {
+    state.index().path_points(path.link()).iter().map(|(idx, link)| {
+        (*idx, link.document(state.store()))
+    }).collect()
+}
+
+
+//------------ svg -----------------------------------------------------------
+
+/// The size of the outline.
+const WIDTH: f64 = 800.;
+const HEIGHT: f64 = 600.;
+const MARGIN: f64 = 40.;
+
+const STYLE: &str = "\
+    .path { fill: none; stroke: #1d3053; stroke-width: 3; }\
+    .end { fill: #1d3053; }\
+    .point { fill: #fff; stroke: #1d3053; stroke-width: 2; }\
+    .label { font: 11px sans-serif; fill: #2e3436; }\
+";
+
+/// Draws the outline of the path with the points located on it.
+pub fn svg(path: path::Document, state: &RequestState) -> Svg {
+    let coords = geo::path_coords(path);
+    let mut svg = Svg::new(WIDTH, HEIGHT);
+    svg.style(STYLE);
+    let projection = match Projection::fit(&coords, WIDTH, HEIGHT, MARGIN) {
+        Some(projection) => projection,
+        None => return svg
+    };
+
+    svg.polyline(
+        coords.iter().map(|coord| projection.project(*coord)),
+        "path"
+    );
+    for coord in [coords.first(), coords.last()].into_iter().flatten() {
+        svg.circle(projection.project(*coord), 5., "end");
+    }
+    for (idx, point) in points(path, state) {
+        let pos = projection.project(coords[idx]);
+        svg.start_link(&json::attr(point.link().href(state)));
+        svg.circle(pos, 4., "point");
+        svg.text(
+            (pos.0 + 7., pos.1 + 4.), 0., "label",
+            &json::text(snip::point::title(point, state))
+        );
+        svg.end_link();
+    }
+    svg
+}
+
+
+//------------ gpx -----------------------------------------------------------
+
+/// Returns the path as a GPX track.
+pub fn gpx(path: path::Document, state: &RequestState) -> Gpx {
+    let title = json::text(snip::path::title(path, state));
+    let mut gpx = Gpx::new(&title);
+    gpx.track(&title, geo::path_coords(path));
+    gpx
+}
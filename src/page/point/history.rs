@@ -0,0 +1,126 @@
+use htmlfn::html;
+use htmlfn::core::Content;
+use htmlfn::utils::{either, iter};
+use raildata::document::point;
+use crate::{event, i18n};
+use crate::event::Cited;
+use crate::page::{frame, snip};
+use crate::page::snip::source::Footnotes;
+use crate::state::RequestState;
+use super::overview::headline;
+use super::property;
+
+pub fn page<'a>(
+    point: point::Document<'a>, state: &'a RequestState
+) -> impl frame::Page + 'a {
+    frame::standard(state, snip::point::title(point, state), (), (),
+        (
+            headline(point, state),
+            history(point, state),
+        )
+    )
+}
+
+
+//------------ history -------------------------------------------------------
+
+pub fn history<'a>(
+    point: point::Document<'a>, state: &'a RequestState
+) -> impl Content + 'a {
+    let notes = footnotes(point, state);
+    let events = event::sorted_events(
+        point.data().events.iter(), |event| &event.date
+    );
+    let refs: Vec<_> = events.iter().map(|event| {
+        notes.refs(event.sources(), state)
+    }).collect();
+    (
+        html::h2(i18n::term::point::history(state)),
+        either(events.is_empty(),
+            || html::p(i18n::term::point::history::empty(state)),
+            || html::ol::class("point-history",
+                iter(events.into_iter().zip(refs).map(
+                    move |(event, refs)| item(event, refs, state)
+                ))
+            )
+        ),
+        notes.list(state),
+    )
+}
+
+fn item<'a>(
+    event: &'a point::Event,
+    refs: impl Content + 'a,
+    state: &'a RequestState
+) -> impl Content + 'a {
+    let lang = state.lang().into();
+    html::li((
+        html::p::class("point-history-date", (
+            snip::date::event_date(&event.date, state),
+            refs,
+        )),
+        html::dl::class("point-history-properties", (
+            // Status
+            event.status.as_ref().map(|status| {(
+                html::dt(i18n::term::point::property::status(state)),
+                html::dd(property::status(status.to_value(), state)),
+            )}),
+
+            // Name
+            event.name(lang).map(|name| {(
+                html::dt(i18n::term::point::property::name(state)),
+                html::dd(name),
+            )}),
+
+            // Designation
+            event.designation(lang).map(|name| {(
+                html::dt(i18n::term::point::property::designation(state)),
+                html::dd(name),
+            )}),
+
+            // category
+            event.category.as_ref().map(|category| {(
+                html::dt(i18n::term::point::property::category(state)),
+                html::dd(html::ul(
+                    iter(category.iter().map(|cat| {
+                        html::li(i18n::enums::point::category(
+                            cat.into_value(), state
+                        ))
+                    }))
+                )),
+            )}),
+
+            // de_rang
+            event.de_rang.as_ref().map(|rang| {(
+                html::dt(i18n::term::point::property::de_rang(state)),
+                html::dd(
+                    i18n::enums::point::de_rang(rang.into_value(), state)
+                ),
+            )}),
+
+            // codes
+            iter(event.codes.iter().map(|(code, value)| {(
+                html::dt(
+                    i18n::enums::point::code_type(code, state)
+                ),
+                html::dd(html::ul(
+                    iter(value.map(|item| {
+                        html::li(item)
+                    }))
+                ))
+            )})),
+        )),
+    ))
+}
+
+
+//------------ Sources -------------------------------------------------------
+
+/// Returns the footnotes for all sources cited by the point’s events.
+pub fn footnotes<'a>(
+    point: point::Document<'a>, state: &'a RequestState
+) -> Footnotes<'a> {
+    Footnotes::new(
+        point.data().events.iter().flat_map(Cited::sources), state
+    )
+}
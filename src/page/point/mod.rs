@@ -1,5 +1,6 @@
+pub use self::history::page as history;
 pub use self::overview::page as overview;
 
+mod history;
 mod overview;
-mod property;
-
+pub mod property;
@@ -1,19 +1,29 @@
 use htmlfn::html;
 use htmlfn::core::Content;
 use htmlfn::utils::iter;
-use raildata::document::point;
-use crate::i18n;
-use crate::page::{frame, snip};
+use raildata::document::{Document, point};
+use crate::{i18n, route};
+use crate::page::{frame, snip, structure};
+use crate::page::snip::source::Footnotes;
 use crate::state::RequestState;
-use super::property;
+use super::{history, property};
 
 pub fn page<'a>(
     point: point::Document<'a>, state: &'a RequestState
 ) -> impl frame::Page + 'a {
+    let notes = history::footnotes(point, state);
     frame::standard(state, snip::point::title(point, state), (), (),
         (
             headline(point, state),
-            current(point, state),
+            current(point, &notes, state),
+            html::p::class("point-history-link",
+                html::a(
+                    route::point::History::href(point, state),
+                    i18n::term::point::history::link(state)
+                )
+            ),
+            notes.list(state),
+            snip::cite::cite(Document::Point(point), state),
         )
     )
 }
@@ -27,48 +37,62 @@ pub fn headline<'a>(
 
 pub fn current<'a>(
     point: point::Document<'a>,
+    notes: &Footnotes<'a>,
     state: &'a RequestState
 ) -> impl Content + 'a {
     let current = &point.meta().current;
+    let events = &point.data().events;
+    let lang = state.lang().into();
     (
         html::h2(i18n::term::point::current(state)),
         html::dl::class("section-current", (
             // Status
             current.status.as_ref().map(|status| {(
                 html::dt(i18n::term::point::property::status(state)),
-                html::dd(property::status(status.to_value(), state)),
+                html::dd((
+                    property::status(status.to_value(), state),
+                    notes.current_refs(events.iter(), |event| {
+                        event.status.is_some()
+                    }, state),
+                )),
             )}),
 
             // Name
             current.name.as_ref().map(|name| {(
                 html::dt(i18n::term::point::property::name(state)),
-                html::dd(
+                html::dd((
                     html::dl::class("local-names",
                         iter(name.iter().map(|(code, name)| {(
                             html::dt(snip::local::opt_local_code(code, state)),
                             html::dd(name.as_value().as_str()),
                         )}))
-                    )
-                )
+                    ),
+                    notes.current_refs(events.iter(), move |event| {
+                        event.name(lang).is_some()
+                    }, state),
+                ))
             )}),
 
             // Short Name
             current.short_name.as_ref().map(|name| {(
                 html::dt(i18n::term::point::property::short_name(state)),
-                html::dd(
+                html::dd((
                     html::dl::class("local-names",
                         iter(name.iter().map(|(code, name)| {(
                             html::dt(snip::local::opt_local_code(code, state)),
                             html::dd(name.as_value().as_str()),
                         )}))
-                    )
-                )
+                    ),
+                    notes.current_refs(events.iter(), |event| {
+                        event.short_name.is_some()
+                    }, state),
+                ))
             )}),
 
             // Public Name
             current.public_name.as_ref().map(|name| {(
                 html::dt(i18n::term::point::property::public_name(state)),
-                html::dd(
+                html::dd((
                     html::dl::class("local-names",
                         iter(
                             name.iter().map(|name| name.iter()).flatten()
@@ -79,47 +103,66 @@ pub fn current<'a>(
                                 html::dd(name.as_value().as_str()),
                             )})
                         )
-                    )
-                )
+                    ),
+                    notes.current_refs(events.iter(), |event| {
+                        event.public_name.is_some()
+                    }, state),
+                ))
             )}),
 
             // Designation
             current.designation.as_ref().map(|name| {(
                 html::dt(i18n::term::point::property::designation(state)),
-                html::dd(
+                html::dd((
                     html::dl::class("local-names",
                         iter(name.iter().map(|(code, name)| {(
                             html::dt(snip::local::opt_local_code(code, state)),
                             html::dd(name.as_value().as_str()),
                         )}))
-                    )
-                )
+                    ),
+                    notes.current_refs(events.iter(), move |event| {
+                        event.designation(lang).is_some()
+                    }, state),
+                ))
             )}),
 
             // de::name16
             current.de_name16.as_ref().map(|name| {(
                 html::dt(i18n::term::point::property::de_name16(state)),
-                html::dd(name.as_str()),
+                html::dd((
+                    name.as_str(),
+                    notes.current_refs(events.iter(), |event| {
+                        event.de_name16.is_some()
+                    }, state),
+                )),
             )}),
 
             // category
             current.category.as_ref().map(|category| {(
                 html::dt(i18n::term::point::property::category(state)),
-                html::dd(html::ul(
-                    iter(category.iter().map(|cat| {
-                        html::li(i18n::enums::point::category(
-                            cat.into_value(), state
-                        ))
-                    }))
+                html::dd((
+                    html::ul(
+                        iter(category.iter().map(|cat| {
+                            html::li(i18n::enums::point::category(
+                                cat.into_value(), state
+                            ))
+                        }))
+                    ),
+                    notes.current_refs(events.iter(), |event| {
+                        event.category.is_some()
+                    }, state),
                 )),
             )}),
 
             // de_rang
             current.de_rang.as_ref().map(|rang| {(
                 html::dt(i18n::term::point::property::de_rang(state)),
-                html::dd(
-                    i18n::enums::point::de_rang(rang.into_value(), state)
-                ),
+                html::dd((
+                    i18n::enums::point::de_rang(rang.into_value(), state),
+                    notes.current_refs(events.iter(), |event| {
+                        event.de_rang.is_some()
+                    }, state),
+                )),
             )}),
 
             // superior
@@ -127,12 +170,17 @@ pub fn current<'a>(
                 opt.as_ref()
             }).flatten().map(|sup| {(
                 html::dt(i18n::term::point::property::superior(state)),
-                html::dd(html::ul(
-                    iter(sup.iter().map(|link| {
-                        html::li(snip::point::link(
-                            link.document(state.store()), None, state
-                        ))
-                    }))
+                html::dd((
+                    html::ul(
+                        iter(sup.iter().map(|link| {
+                            html::li(snip::point::link(
+                                link.document(state.store()), None, state
+                            ))
+                        }))
+                    ),
+                    notes.current_refs(events.iter(), |event| {
+                        event.superior.is_some()
+                    }, state),
                 ))
             )}),
 
@@ -141,10 +189,15 @@ pub fn current<'a>(
                 html::dt(
                     i18n::enums::point::code_type(code, state)
                 ),
-                html::dd(html::ul(
-                    iter(value.map(|item| {
-                        html::li(item)
-                    }))
+                html::dd((
+                    html::ul(
+                        iter(value.map(|item| {
+                            html::li(item)
+                        }))
+                    ),
+                    notes.current_refs(events.iter(), move |event| {
+                        event.codes.iter().any(|(item, _)| item == code)
+                    }, state),
                 ))
             )})),
 
@@ -160,11 +213,22 @@ pub fn current<'a>(
                                 line.document(state.store()), state
                             )
                         ),
-                        html::dd(loc.unwrap_or("–"))
+                        html::dd((
+                            loc.unwrap_or("–"),
+                            notes.current_refs(events.iter(), move |event| {
+                                event.location.as_ref().map(|location| {
+                                    location.iter().any(|(item, _)| {
+                                        item == line
+                                    })
+                                }).unwrap_or(false)
+                            }, state),
+                        ))
                     )}))
                 ))
-            )})
+            )}),
+
+            // structures
+            structure::structures(Document::Point(point), state),
         ))
     )
 }
-
@@ -3,12 +3,15 @@ use htmlfn::core::{Content, Text};
 use raildata::document::entity;
 use crate::{i18n, route};
 use crate::state::RequestState;
+use super::hierarchy::breadcrumbs;
 
 
 #[derive(Clone, Copy)]
 pub enum Chapter {
     Overview,
     Lines,
+    Points,
+    Statistics,
 }
 
 impl Chapter {
@@ -22,6 +25,7 @@ impl Chapter {
         self, entity: entity::Document<'a>, state: &'a RequestState
     ) -> impl Content + 'a {
         (
+            breadcrumbs(entity, state),
             html::h1(entity.data().local_short_name(state.lang().into())),
             html::div::class("standard-subpage-nav",
                 html::ul((
@@ -39,6 +43,20 @@ impl Chapter {
                             i18n::term::entity::subpage::lines(state)
                         )
                     ),
+                    html::li::class(
+                        matches!(self, Self::Points).then(|| "active"),
+                        html::a(
+                            route::region::Points::href(entity, state),
+                            i18n::term::region::subpage::points(state)
+                        )
+                    ),
+                    html::li::class(
+                        matches!(self, Self::Statistics).then(|| "active"),
+                        html::a(
+                            route::region::Statistics::href(entity, state),
+                            i18n::term::region::subpage::statistics(state)
+                        )
+                    ),
                 ))
             )
         )
@@ -0,0 +1,193 @@
+//! The hierarchy of regions.
+//!
+//! Countries and regions form a tree via their superior entities. Only
+//! the child knows about its parents, so the tree is collected once into
+//! the index when the database is loaded.
+
+use std::collections::HashSet;
+use htmlfn::html;
+use htmlfn::core::Content;
+use htmlfn::utils::{display, iter, join};
+use raildata::document::entity;
+use crate::i18n;
+use crate::page::snip;
+use crate::state::RequestState;
+use super::points;
+
+
+//------------ Tree ----------------------------------------------------------
+
+/// All countries and regions with their parents.
+///
+/// This is a view of the region tree kept in the index that returns
+/// documents sorted by name in the language of the request.
+pub struct Tree<'a> {
+    state: &'a RequestState,
+}
+
+impl<'a> Tree<'a> {
+    pub fn new(state: &'a RequestState) -> Self {
+        Tree { state }
+    }
+
+    /// Returns the parent regions of a region.
+    pub fn parents(
+        &self, entity: entity::Document<'a>
+    ) -> Vec<entity::Document<'a>> {
+        self.documents(self.state.index().region_parents(entity.link()))
+    }
+
+    /// Returns the child regions of a region sorted by name.
+    pub fn children(
+        &self, entity: entity::Document<'a>
+    ) -> Vec<entity::Document<'a>> {
+        self.sorted(self.state.index().region_children(entity.link()))
+    }
+
+    /// Returns the regions without a parent sorted by name.
+    pub fn roots(&self) -> Vec<entity::Document<'a>> {
+        self.sorted(self.state.index().region_roots())
+    }
+
+    fn documents(
+        &self, links: &[entity::Link]
+    ) -> Vec<entity::Document<'a>> {
+        let store = self.state.store();
+        links.iter().map(|link| link.document(store)).collect()
+    }
+
+    fn sorted(&self, links: &[entity::Link]) -> Vec<entity::Document<'a>> {
+        let mut res = self.documents(links);
+        res.sort_by_key(|entity| {
+            entity.data().local_short_name(self.state.lang().into())
+        });
+        res
+    }
+
+    /// Returns all descendants of a region with their depth.
+    ///
+    /// The descendants are returned depth first starting with the children
+    /// at depth 0. Each region is returned only once.
+    pub fn descendants(
+        &self, entity: entity::Document<'a>
+    ) -> Vec<(usize, entity::Document<'a>)> {
+        let mut res = Vec::new();
+        let mut seen = HashSet::from([entity.link()]);
+        self.walk(entity, 0, &mut seen, &mut res);
+        res
+    }
+
+    fn walk(
+        &self,
+        entity: entity::Document<'a>,
+        depth: usize,
+        seen: &mut HashSet<entity::Link>,
+        res: &mut Vec<(usize, entity::Document<'a>)>,
+    ) {
+        for child in self.children(entity) {
+            if !seen.insert(child.link()) {
+                continue
+            }
+            res.push((depth, child));
+            self.walk(child, depth + 1, seen, res);
+        }
+    }
+
+    /// Returns the ancestors of a region starting with the root.
+    ///
+    /// If a region has more than one parent, the first one is followed.
+    pub fn ancestors(
+        &self, entity: entity::Document<'a>
+    ) -> Vec<entity::Document<'a>> {
+        let store = self.state.store();
+        let index = self.state.index();
+        let mut res: Vec<entity::Document> = Vec::new();
+        let mut seen = HashSet::from([entity.link()]);
+        let mut current = entity.link();
+        while let Some(parent) = index.region_parents(current).first() {
+            if !seen.insert(*parent) {
+                break
+            }
+            res.push(parent.document(store));
+            current = *parent;
+        }
+        res.reverse();
+        res
+    }
+
+    /// Returns the number of lines and points in a region.
+    pub fn counts(&self, entity: entity::Document<'a>) -> (usize, usize) {
+        let lines: HashSet<_> = entity.xrefs().line_regions.iter().map(
+            |(link, _)| *link
+        ).collect();
+        (lines.len(), points::count(entity, self.state))
+    }
+}
+
+
+//------------ breadcrumbs ---------------------------------------------------
+
+/// The path from the root of the hierarchy to a region.
+///
+/// Renders nothing if the region has no parent.
+pub fn breadcrumbs<'a>(
+    entity: entity::Document<'a>, state: &'a RequestState
+) -> impl Content + 'a {
+    let ancestors = Tree::new(state).ancestors(entity);
+    (!ancestors.is_empty()).then(|| {
+        html::div::class("region-breadcrumbs",
+            join(" › ", ancestors.into_iter().map(move |ancestor| {
+                snip::entity::link(ancestor, state)
+            }))
+        )
+    })
+}
+
+
+//------------ hierarchy -----------------------------------------------------
+
+/// The parents and children of a region.
+///
+/// Renders nothing if the region has neither.
+pub fn hierarchy<'a>(
+    entity: entity::Document<'a>, state: &'a RequestState
+) -> impl Content + 'a {
+    let tree = Tree::new(state);
+    let parents = tree.parents(entity);
+    let children: Vec<_> = tree.children(entity).into_iter().map(|child| {
+        (child, tree.counts(child))
+    }).collect();
+    (!parents.is_empty() || !children.is_empty()).then(|| {(
+        html::h2(i18n::term::region::hierarchy::title(state)),
+        html::dl::class("region-hierarchy", (
+            // Parents
+            (!parents.is_empty()).then(|| {(
+                html::dt(i18n::term::region::hierarchy::parent(state)),
+                html::dd(
+                    join(", ", parents.into_iter().map(move |parent| {
+                        snip::entity::link(parent, state)
+                    }))
+                ),
+            )}),
+
+            // Children
+            (!children.is_empty()).then(|| {(
+                html::dt(i18n::term::region::hierarchy::children(state)),
+                html::dd(html::ul::class("region-children",
+                    iter(children.into_iter().map(move |(child, counts)| {
+                        html::li((
+                            snip::entity::link(child, state),
+                            " (",
+                            display(counts.0), " ",
+                            i18n::term::region::hierarchy::lines(state),
+                            ", ",
+                            display(counts.1), " ",
+                            i18n::term::region::hierarchy::points(state),
+                            ")",
+                        ))
+                    }))
+                )),
+            )}),
+        ))
+    )})
+}
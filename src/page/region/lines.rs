@@ -1,11 +1,24 @@
+//! The list of lines in a region.
+//!
+//! The lines are split into open, closed, planned, and removed lines. Each
+//! row shows the parts of a line inside the region and its current
+//! properties there. The list can be sorted by column and filtered by
+//! status via the query parameters `sort` and `status`.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
 use htmlfn::html;
 use htmlfn::core::Content;
-use htmlfn::utils::iter;
-use raildata::document::{entity, line};
+use htmlfn::utils::{either, iter, join};
+use raildata::document::{entity, line, point};
+use raildata::document::line::{Category, Status};
+use crate::{i18n, json};
 use crate::page::{frame, snip};
-use crate::route::Href;
+use crate::page::line::property;
 use crate::state::RequestState;
 use super::components::Chapter;
+use super::section::LinePoints;
 
 const CHAPTER: Chapter = Chapter::Lines;
 
@@ -13,31 +26,537 @@ const CHAPTER: Chapter = Chapter::Lines;
 pub fn page<'a>(
     entity: entity::Document<'a>, state: &'a RequestState
 ) -> impl frame::Page + 'a {
+    let query = Query::from_query(state);
+    let mut rows: Vec<_> = sections(entity).into_iter().filter_map(
+        |(link, sections)| {
+            Row::new(link.document(state.store()), &sections, state)
+        }
+    ).collect();
+    query.sort(&mut rows);
+    let groups: Vec<_> = Group::ALL.into_iter().filter(|group| {
+        query.status.map(|status| status == *group).unwrap_or(true)
+    }).map(|group| {
+        let rows: Vec<_> = rows.iter().filter(|row| {
+            row.group == group
+        }).cloned().collect();
+        (group, rows)
+    }).filter(|(_, rows)| !rows.is_empty()).collect();
     frame::standard(state, CHAPTER.title(entity, state), (), (), (
         CHAPTER.headline(entity, state),
-        html::table(
-            iter(entity.xrefs().line_regions.iter().map(|(link, section)| {
-                item(link.document(state.store()), section, state)
-            }))
+        filter(query, state),
+        either(groups.is_empty(),
+            || html::p(i18n::term::region::lines::empty(state)),
+            move || iter(groups.into_iter().map(move |(group, rows)| {(
+                html::h2(group.title(state)),
+                html::table::class(group.class(), (
+                    header(query, state),
+                    iter(rows.into_iter().map(move |row| item(row, state)))
+                ))
+            )}))
         )
     ))
 }
 
-fn item<'a>(
-    line: line::Document<'a>,
-    _section: &'a line::Section,
-    state: &'a RequestState
-) -> impl Content + 'a {
-    html::tr((
-        html::td(
+/// Returns the sections inside the region grouped by line.
+///
+/// Lines keep the order of their first section.
+fn sections(
+    entity: entity::Document
+) -> Vec<(line::Link, Vec<&line::Section>)> {
+    let mut res: Vec<(line::Link, Vec<&line::Section>)> = Vec::new();
+    let mut index = HashMap::new();
+    for (link, section) in entity.xrefs().line_regions.iter() {
+        let idx = *index.entry(*link).or_insert_with(|| {
+            res.push((*link, Vec::new()));
+            res.len() - 1
+        });
+        res[idx].1.push(section);
+    }
+    res
+}
+
+/// The links for filtering by status.
+fn filter<'a>(query: Query, state: &'a RequestState) -> impl Content + 'a {
+    html::ul::class("region-lines-filter", (
+        html::li::class(query.status.is_none().then(|| "active"),
             html::a(
-                line.href(state),
-                line.data().code().as_str(),
+                ("?", Query { status: None, ..query }.to_string()),
+                i18n::term::region::lines::all(state)
+            )
+        ),
+        iter(Group::ALL.into_iter().map(move |group| {
+            html::li::class((query.status == Some(group)).then(|| "active"),
+                html::a(
+                    ("?", Query { status: Some(group), ..query }.to_string()),
+                    group.title(state)
+                )
+            )
+        }))
+    ))
+}
+
+/// The header row with links for sorting.
+///
+/// Clicking on the column currently sorted by reverses the order.
+fn header<'a>(query: Query, state: &'a RequestState) -> impl Content + 'a {
+    html::tr(
+        iter(Sort::ALL.into_iter().map(move |sort| {
+            let current = query.sort == sort;
+            html::th::class(current.then(|| "sorted"),
+                html::a(
+                    (
+                        "?",
+                        Query {
+                            sort, desc: current && !query.desc, ..query
+                        }.to_string()
+                    ),
+                    (
+                        sort.title(state),
+                        current.then(|| {
+                            if query.desc { " ▾" } else { " ▴" }
+                        }),
+                    )
+                )
             )
+        }))
+    )
+}
+
+fn item<'a>(row: Row<'a>, state: &'a RequestState) -> impl Content + 'a {
+    let jurisdiction = row.line.data().jurisdiction();
+    html::tr((
+        html::td(
+            snip::line::code_link(row.line, state)
         ),
         html::td(
-            snip::line::line_title(line, state)
-        )
+            snip::line::line_title(row.line, state)
+        ),
+        html::td((
+            snip::point::link(row.start, jurisdiction, state),
+            " – ",
+            snip::point::link(row.end, jurisdiction, state),
+        )),
+        html::td(join(", ", row.status.into_iter().map(move |status| {
+            property::status(status, state)
+        }))),
+        html::td(join(" ", row.category.into_iter().map(move |cat| {
+            property::category(cat, state)
+        }))),
+        html::td(join("/", row.gauge.into_iter().map(|gauge| {
+            (gauge, "\u{202f}mm")
+        }))),
+        html::td::class("region-lines-length", (
+            format!("{:.1}", row.length / 1000.),
+            "\u{202f}km"
+        )),
     ))
 }
 
+
+//------------ Row -----------------------------------------------------------
+
+/// The parts of a line inside the region.
+#[derive(Clone)]
+struct Row<'a> {
+    line: line::Document<'a>,
+    title: String,
+
+    /// The first point of the first part.
+    start: point::Document<'a>,
+
+    /// The last point of the last part.
+    end: point::Document<'a>,
+
+    status: Vec<Status>,
+    category: Vec<Category>,
+    gauge: Vec<String>,
+
+    /// The length of the parts in metres.
+    ///
+    /// This is taken from the locations of the points on the line where
+    /// available and from their coordinates otherwise.
+    length: f64,
+
+    group: Group,
+}
+
+impl<'a> Row<'a> {
+    /// Creates the row for the given sections of a line.
+    ///
+    /// Sections whose end points aren’t on the line are skipped. If no
+    /// section is left, there is no row.
+    fn new(
+        line: line::Document<'a>,
+        sections: &[&'a line::Section],
+        state: &'a RequestState
+    ) -> Option<Self> {
+        let store = state.store();
+        let points = LinePoints::new(line, store);
+        let part_of = |section: &line::Section| {
+            points.range(section, store).map(|(start, end)| {
+                Part { start, end }
+            })
+        };
+        let parts: Vec<_> = sections.iter().filter_map(|section| {
+            part_of(section)
+        }).collect();
+        if parts.is_empty() {
+            return None
+        }
+        let overlaps = |section: &line::Section| {
+            part_of(section).map(|other| {
+                parts.iter().any(|part| part.overlaps(other))
+            }).unwrap_or(false)
+        };
+        let current = &line.data().current;
+
+        let mut status = Vec::new();
+        for (section, value) in current.status.as_slice().iter() {
+            if overlaps(section) && !status.contains(value) {
+                status.push(*value)
+            }
+        }
+        let mut category = Vec::new();
+        for (section, value) in current.category.as_slice().iter() {
+            if !overlaps(section) {
+                continue
+            }
+            for cat in value.iter() {
+                if !category.contains(cat) {
+                    category.push(*cat)
+                }
+            }
+        }
+        let mut gauge = Vec::new();
+        for (section, value) in current.gauge.as_slice().iter() {
+            if !overlaps(section) {
+                continue
+            }
+            for item in value.iter() {
+                let item = item.gauge().to_string();
+                if !gauge.contains(&item) {
+                    gauge.push(item)
+                }
+            }
+        }
+
+        let length = parts.iter().map(|part| {
+            points.length(part.start, part.end, state)
+        }).sum();
+        let start = parts.iter().map(|part| part.start).min().unwrap_or(0);
+        let end = parts.iter().map(|part| part.end).max().unwrap_or(0);
+
+        Some(Row {
+            line,
+            title: json::text(snip::line::line_title(line, state)),
+            start: points.points()[start],
+            end: points.points()[end],
+            group: Group::from_statuses(&status),
+            status,
+            category,
+            gauge,
+            length,
+        })
+    }
+
+    /// Compares two rows by the given column.
+    fn compare(&self, other: &Self, sort: Sort) -> Ordering {
+        match sort {
+            Sort::Code => {
+                self.line.data().code().as_str().cmp(
+                    other.line.data().code().as_str()
+                )
+            }
+            Sort::Title => self.title.cmp(&other.title),
+            Sort::Section => {
+                self.start.data().name_in_jurisdiction(None).cmp(
+                    other.start.data().name_in_jurisdiction(None)
+                )
+            }
+            Sort::Status => {
+                self.status.first().map(|status| status_rank(*status)).cmp(
+                    &other.status.first().map(|status| status_rank(*status))
+                )
+            }
+            Sort::Category => {
+                self.category.first().map(|cat| cat.short_str()).cmp(
+                    &other.category.first().map(|cat| cat.short_str())
+                )
+            }
+            Sort::Gauge => {
+                let gauge = |row: &Self| {
+                    row.gauge.first().and_then(|gauge| {
+                        gauge.parse::<u32>().ok()
+                    })
+                };
+                gauge(self).cmp(&gauge(other))
+            }
+            Sort::Length => {
+                self.length.partial_cmp(&other.length).unwrap_or(
+                    Ordering::Equal
+                )
+            }
+        }
+    }
+}
+
+/// Returns the position of a status in the life cycle of a line.
+fn status_rank(status: Status) -> u8 {
+    use self::Status::*;
+
+    match status {
+        None => 0,
+        Planned => 1,
+        Construction => 2,
+        Open => 3,
+        Reopened => 4,
+        Suspended => 5,
+        Closed => 6,
+        Released => 7,
+        Removed => 8,
+    }
+}
+
+
+//------------ Part ----------------------------------------------------------
+
+/// A part of a line given as the indexes of its first and last point.
+#[derive(Clone, Copy)]
+struct Part {
+    start: usize,
+    end: usize,
+}
+
+impl Part {
+    /// Returns whether two parts share at least one segment.
+    ///
+    /// If either part is a single point, sharing that point is enough.
+    fn overlaps(self, other: Part) -> bool {
+        if self.start == self.end || other.start == other.end {
+            self.start <= other.end && other.start <= self.end
+        }
+        else {
+            self.start.max(other.start) < self.end.min(other.end)
+        }
+    }
+}
+
+
+//------------ Group ---------------------------------------------------------
+
+/// The groups lines are split into.
+///
+/// The groups are ordered by precedence: if the parts of a line fall into
+/// different groups, the line is shown in the first of them.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum Group {
+    Open,
+    Closed,
+    Planned,
+    Removed,
+}
+
+impl Group {
+    const ALL: [Self; 4] = [
+        Group::Open, Group::Closed, Group::Planned, Group::Removed
+    ];
+
+    /// Determines the group from the statuses of the parts of a line.
+    ///
+    /// Lines without a known status count as open.
+    fn from_statuses(statuses: &[Status]) -> Self {
+        statuses.iter().map(|status| {
+            Self::from_status(*status)
+        }).min().unwrap_or(Group::Open)
+    }
+
+    /// Determines the group for a single status.
+    fn from_status(status: Status) -> Self {
+        match status {
+            Status::Open | Status::Reopened => Group::Open,
+            Status::Suspended | Status::Closed => Group::Closed,
+            Status::None | Status::Planned | Status::Construction => {
+                Group::Planned
+            }
+            Status::Released | Status::Removed => Group::Removed,
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "open" => Some(Group::Open),
+            "closed" => Some(Group::Closed),
+            "planned" => Some(Group::Planned),
+            "removed" => Some(Group::Removed),
+            _ => None
+        }
+    }
+
+    fn code(self) -> &'static str {
+        match self {
+            Group::Open => "open",
+            Group::Closed => "closed",
+            Group::Planned => "planned",
+            Group::Removed => "removed",
+        }
+    }
+
+    fn class(self) -> &'static str {
+        match self {
+            Group::Open => "region-lines region-lines-open",
+            Group::Closed => "region-lines region-lines-closed",
+            Group::Planned => "region-lines region-lines-planned",
+            Group::Removed => "region-lines region-lines-removed",
+        }
+    }
+
+    fn title(self, state: &RequestState) -> &'static str {
+        match self {
+            Group::Open => i18n::term::region::lines::open(state),
+            Group::Closed => i18n::term::region::lines::closed(state),
+            Group::Planned => i18n::term::region::lines::planned(state),
+            Group::Removed => i18n::term::region::lines::removed(state),
+        }
+    }
+}
+
+
+//------------ Sort ----------------------------------------------------------
+
+/// The columns the list can be sorted by.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Sort {
+    Code,
+    Title,
+    Section,
+    Status,
+    Category,
+    Gauge,
+    Length,
+}
+
+impl Sort {
+    const ALL: [Self; 7] = [
+        Sort::Code, Sort::Title, Sort::Section, Sort::Status,
+        Sort::Category, Sort::Gauge, Sort::Length,
+    ];
+
+    fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|sort| sort.code() == code)
+    }
+
+    fn code(self) -> &'static str {
+        match self {
+            Sort::Code => "code",
+            Sort::Title => "title",
+            Sort::Section => "section",
+            Sort::Status => "status",
+            Sort::Category => "category",
+            Sort::Gauge => "gauge",
+            Sort::Length => "length",
+        }
+    }
+
+    fn title(self, state: &RequestState) -> &'static str {
+        match self {
+            Sort::Code => i18n::term::region::lines::column::code(state),
+            Sort::Title => i18n::term::region::lines::column::title(state),
+            Sort::Section => {
+                i18n::term::region::lines::column::section(state)
+            }
+            Sort::Status => i18n::term::region::lines::column::status(state),
+            Sort::Category => {
+                i18n::term::region::lines::column::category(state)
+            }
+            Sort::Gauge => i18n::term::region::lines::column::gauge(state),
+            Sort::Length => i18n::term::region::lines::column::length(state),
+        }
+    }
+}
+
+
+//------------ Query ---------------------------------------------------------
+
+/// The sorting and filtering requested via the query.
+///
+/// The `sort` parameter contains the code of the column to sort by. If it
+/// is prefixed with a minus sign, the order is reversed. The `status`
+/// parameter contains the code of the group to show.
+#[derive(Clone, Copy, Debug)]
+struct Query {
+    sort: Sort,
+    desc: bool,
+    status: Option<Group>,
+}
+
+impl Query {
+    fn from_query(state: &RequestState) -> Self {
+        let query = state.query();
+        let (sort, desc) = match query.get_first("sort") {
+            Some(sort) => match sort.strip_prefix('-') {
+                Some(sort) => (Sort::from_code(sort), true),
+                None => (Sort::from_code(sort), false),
+            }
+            None => (None, false)
+        };
+        Query {
+            sort: sort.unwrap_or(Sort::Code),
+            desc: sort.is_some() && desc,
+            status: query.get_first("status").and_then(Group::from_code),
+        }
+    }
+
+    fn sort(self, rows: &mut [Row]) {
+        rows.sort_by(|left, right| {
+            let res = left.compare(right, self.sort).then_with(|| {
+                left.compare(right, Sort::Code)
+            });
+            if self.desc { res.reverse() } else { res }
+        })
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "sort={}{}",
+            if self.desc { "-" } else { "" }, self.sort.code()
+        )?;
+        if let Some(status) = self.status {
+            write!(f, "&status={}", status.code())?;
+        }
+        Ok(())
+    }
+}
+
+
+//------------ Tests ---------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn group_from_statuses() {
+        assert_eq!(Group::from_statuses(&[]), Group::Open);
+        assert_eq!(
+            Group::from_statuses(&[Status::Removed, Status::Reopened]),
+            Group::Open
+        );
+        assert_eq!(
+            Group::from_statuses(&[Status::Planned, Status::Suspended]),
+            Group::Closed
+        );
+        assert_eq!(
+            Group::from_statuses(&[Status::Construction, Status::Released]),
+            Group::Planned
+        );
+        assert_eq!(
+            Group::from_statuses(&[Status::None]),
+            Group::Planned
+        );
+        assert_eq!(
+            Group::from_statuses(&[Status::Released, Status::Removed]),
+            Group::Removed
+        );
+    }
+}
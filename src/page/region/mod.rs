@@ -1,8 +1,15 @@
 
 pub use self::overview::page as overview;
 pub use self::lines::page as lines;
+pub use self::points::page as points;
+pub use self::statistics::page as statistics;
+
+pub mod hierarchy;
+pub mod statistics;
 
 mod components;
 mod lines;
 mod overview;
+mod points;
 mod property;
+mod section;
@@ -1,11 +1,12 @@
 use htmlfn::html;
 use htmlfn::core::Content;
 use htmlfn::utils::{iter, join};
-use raildata::document::entity;
+use raildata::document::{Document, entity};
 use crate::i18n;
 use crate::page::{frame, snip};
 use crate::state::RequestState;
 use super::components::Chapter;
+use super::hierarchy::hierarchy;
 use super::property;
 
 const CHAPTER: Chapter = Chapter::Overview;
@@ -16,6 +17,8 @@ pub fn page<'a>(
     frame::standard(state, CHAPTER.title(entity, state), (), (), (
         CHAPTER.headline(entity, state),
         current(entity, state),
+        hierarchy(entity, state),
+        snip::cite::cite(Document::Entity(entity), state),
     ))
 }
 
@@ -0,0 +1,302 @@
+//! The index of points in a region.
+//!
+//! The points are found via the lines in the region: every point on the
+//! part of a line inside the region belongs to the region. The index is
+//! sorted alphabetically and can be filtered by category via the query
+//! parameter `category`.
+
+use std::collections::HashSet;
+use htmlfn::html;
+use htmlfn::core::Content;
+use htmlfn::utils::{either, iter, join};
+use raildata::document::{entity, point};
+use crate::{i18n, json};
+use crate::page::{frame, snip};
+use crate::page::point::property;
+use crate::state::RequestState;
+use super::components::Chapter;
+use super::section::LinePoints;
+
+const CHAPTER: Chapter = Chapter::Points;
+
+
+pub fn page<'a>(
+    entity: entity::Document<'a>, state: &'a RequestState
+) -> impl frame::Page + 'a {
+    let points = points(entity, state);
+    let categories = categories(&points);
+    let selected = state.query().get_first("category").and_then(|code| {
+        categories.iter().copied().find(|cat| category_code(*cat) == code)
+    });
+    let points: Vec<_> = points.into_iter().filter(|item| {
+        selected.map(|cat| item.category.contains(&cat)).unwrap_or(true)
+    }).collect();
+    let letters = letters(points);
+    let initials: Vec<_> = letters.iter().map(|(letter, _)| *letter).collect();
+    frame::standard(state, CHAPTER.title(entity, state), (), (), (
+        CHAPTER.headline(entity, state),
+        filter(categories, selected, state),
+        html::ul::class("region-points-letters",
+            iter(initials.into_iter().map(|letter| {
+                html::li(html::a(("#letter-", letter), letter))
+            }))
+        ),
+        either(letters.is_empty(),
+            || html::p(i18n::term::region::points::empty(state)),
+            move || iter(letters.into_iter().map(move |(letter, points)| {(
+                html::h2::id(("letter-", letter), letter),
+                html::table::class("region-points", (
+                    header(state),
+                    iter(points.into_iter().map(move |item| {
+                        row(item, state)
+                    }))
+                ))
+            )}))
+        )
+    ))
+}
+
+/// The links for filtering by category.
+fn filter<'a>(
+    categories: Vec<point::Category>,
+    selected: Option<point::Category>,
+    state: &'a RequestState
+) -> impl Content + 'a {
+    html::ul::class("region-points-filter", (
+        html::li::class(selected.is_none().then(|| "active"),
+            html::a("?", i18n::term::region::points::all(state))
+        ),
+        iter(categories.into_iter().map(move |cat| {
+            html::li::class((selected == Some(cat)).then(|| "active"),
+                html::a(
+                    ("?category=", category_code(cat)),
+                    i18n::enums::point::category(cat, state)
+                )
+            )
+        }))
+    ))
+}
+
+fn header<'a>(state: &'a RequestState) -> impl Content + 'a {
+    html::tr((
+        html::th(i18n::term::region::points::column::name(state)),
+        html::th(i18n::term::region::points::column::category(state)),
+        html::th(i18n::term::region::points::column::status(state)),
+        html::th(i18n::term::region::points::column::codes(state)),
+        html::th(i18n::term::region::points::column::lines(state)),
+    ))
+}
+
+fn row<'a>(item: Item<'a>, state: &'a RequestState) -> impl Content + 'a {
+    let point = item.point;
+    let current = &point.meta().current;
+    html::tr((
+        html::td(
+            snip::point::link(point, None, state)
+        ),
+        html::td(join(", ", item.category.into_iter().map(move |cat| {
+            i18n::enums::point::category(cat, state)
+        }))),
+        html::td(current.status.as_ref().map(|status| {
+            property::status(status.to_value(), state)
+        })),
+        html::td(join(", ", current.codes.iter().map(|(code, value)| {(
+            i18n::enums::point::code_type(code, state),
+            " ",
+            join("/", value),
+        )}))),
+        html::td(join(", ", point.xrefs().lines.iter().map(move |line| {
+            snip::line::code_link(line.document(state.store()), state)
+        }))),
+    ))
+}
+
+
+//------------ Item ----------------------------------------------------------
+
+/// A point in the index.
+struct Item<'a> {
+    point: point::Document<'a>,
+
+    /// The name used for sorting and for finding the initial letter.
+    name: String,
+
+    /// The current categories of the point.
+    category: Vec<point::Category>,
+}
+
+/// Returns all points in the region sorted by name.
+fn points<'a>(
+    entity: entity::Document<'a>, state: &'a RequestState
+) -> Vec<Item<'a>> {
+    let mut res: Vec<_> = documents(entity, state).into_iter().map(|point| {
+        Item {
+            point,
+            name: json::text(snip::point::title(point, state)),
+            category: point.meta().current.category.as_ref().map(
+                |category| {
+                    category.iter().map(|cat| cat.into_value()).collect()
+                }
+            ).unwrap_or_default(),
+        }
+    }).collect();
+    res.sort_by_cached_key(|item| {
+        (collation_key(&item.name), item.name.clone())
+    });
+    res
+}
+
+/// Returns the number of points in the region.
+pub fn count(entity: entity::Document, state: &RequestState) -> usize {
+    documents(entity, state).len()
+}
+
+/// Returns all points in the region in the order of the lines.
+fn documents<'a>(
+    entity: entity::Document<'a>, state: &'a RequestState
+) -> Vec<point::Document<'a>> {
+    let store = state.store();
+    let mut res: Vec<point::Document> = Vec::new();
+    let mut seen = HashSet::new();
+    for (link, section) in entity.xrefs().line_regions.iter() {
+        let points = LinePoints::new(link.document(store), store);
+        let (start, end) = match points.range(section, store) {
+            Some(range) => range,
+            None => continue
+        };
+        for &point in &points.points()[start..=end] {
+            if seen.insert(point.link()) {
+                res.push(point)
+            }
+        }
+    }
+    res
+}
+
+/// Returns all categories used by the points in the order of first use.
+fn categories(points: &[Item]) -> Vec<point::Category> {
+    let mut res = Vec::new();
+    for item in points {
+        for cat in &item.category {
+            if !res.contains(cat) {
+                res.push(*cat)
+            }
+        }
+    }
+    res
+}
+
+/// Returns the code of a category for use in the query.
+fn category_code(cat: point::Category) -> String {
+    json::code::code(cat).into_owned()
+}
+
+
+//------------ letters -------------------------------------------------------
+
+/// Splits the points by the initial letter of their names.
+///
+/// The points need to be sorted already. Letters with diacritics are
+/// filed with their base letter and everything that doesn’t start with a
+/// letter goes under “#.”
+fn letters(points: Vec<Item>) -> Vec<(&'static str, Vec<Item>)> {
+    let mut res: Vec<(&'static str, Vec<Item>)> = Vec::new();
+    for item in points {
+        let letter = initial(&item.name);
+        match res.iter_mut().find(|(other, _)| *other == letter) {
+            Some((_, items)) => items.push(item),
+            None => res.push((letter, vec![item])),
+        }
+    }
+    res.sort_by_key(|(letter, _)| (*letter == "#", *letter));
+    res
+}
+
+fn initial(name: &str) -> &'static str {
+    const LETTERS: [&str; 26] = [
+        "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M",
+        "N", "O", "P", "Q", "R", "S", "T", "U", "V", "W", "X", "Y", "Z",
+    ];
+
+    let ch = match name.chars().next() {
+        Some(ch) => fold(ch),
+        None => return "#"
+    };
+    if ch.is_ascii_lowercase() {
+        LETTERS[usize::from(ch as u8 - b'a')]
+    }
+    else {
+        "#"
+    }
+}
+
+
+//------------ collation_key -------------------------------------------------
+
+/// Returns the key for sorting a name alphabetically.
+///
+/// Letters are compared without case and with diacritics removed so that,
+/// for instance, “Ölsnitz” is sorted with the O rather than after Z.
+fn collation_key(name: &str) -> String {
+    let mut res = String::with_capacity(name.len());
+    for ch in name.chars() {
+        match ch {
+            'ß' => res.push_str("ss"),
+            'æ' | 'Æ' => res.push_str("ae"),
+            'œ' | 'Œ' => res.push_str("oe"),
+            ch => res.push(fold(ch)),
+        }
+    }
+    res
+}
+
+/// Returns the lower case base letter of a character.
+fn fold(ch: char) -> char {
+    match ch.to_lowercase().next().unwrap_or(ch) {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ą' => 'a',
+        'ç' | 'č' | 'ć' => 'c',
+        'ď' => 'd',
+        'è' | 'é' | 'ê' | 'ë' | 'ě' | 'ę' => 'e',
+        'ì' | 'í' | 'î' | 'ï' => 'i',
+        'ľ' | 'ĺ' | 'ł' => 'l',
+        'ñ' | 'ň' | 'ń' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => 'o',
+        'ř' | 'ŕ' => 'r',
+        'š' | 'ś' => 's',
+        'ť' => 't',
+        'ù' | 'ú' | 'û' | 'ü' | 'ů' => 'u',
+        'ý' | 'ÿ' => 'y',
+        'ž' | 'ź' | 'ż' => 'z',
+        ch => ch,
+    }
+}
+
+
+//------------ Tests ---------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn collation() {
+        let mut names = vec![
+            "Zwickau", "Ölsnitz", "Oberhausen", "Straße", "Strasburg",
+            "Aue", "Ärzen", "Écully", "Essen", "Plauen",
+        ];
+        names.sort_by_cached_key(|name| (collation_key(name), *name));
+        assert_eq!(names, [
+            "Ärzen", "Aue", "Écully", "Essen", "Oberhausen", "Ölsnitz",
+            "Plauen", "Strasburg", "Straße", "Zwickau",
+        ]);
+    }
+
+    #[test]
+    fn initials() {
+        assert_eq!(initial("Ölsnitz"), "O");
+        assert_eq!(initial("écully"), "E");
+        assert_eq!(initial("Żagań"), "Z");
+        assert_eq!(initial("3. Kilometer"), "#");
+        assert_eq!(initial(""), "#");
+    }
+}
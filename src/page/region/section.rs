@@ -0,0 +1,126 @@
+//! The parts of lines inside a region.
+//!
+//! The lines, points, and statistics pages all need to know which points
+//! of a line a section covers and how long that part is. They share the
+//! rules defined here so that they agree on what is inside a region.
+
+use raildata::document::{line, point};
+use raildata::store::FullStore;
+use crate::geo;
+use crate::state::RequestState;
+
+
+//------------ LinePoints ----------------------------------------------------
+
+/// The points of a line for resolving sections.
+pub struct LinePoints<'a> {
+    line: line::Document<'a>,
+    points: Vec<point::Document<'a>>,
+}
+
+impl<'a> LinePoints<'a> {
+    pub fn new(line: line::Document<'a>, store: &'a FullStore) -> Self {
+        LinePoints {
+            line,
+            points: line.data().points.iter_documents(store).collect(),
+        }
+    }
+
+    /// Returns all points of the line in order.
+    pub fn points(&self) -> &[point::Document<'a>] {
+        &self.points
+    }
+
+    /// Returns the index of the last point of the line.
+    pub fn last(&self) -> usize {
+        self.points.len().saturating_sub(1)
+    }
+
+    /// Returns the range of point indexes covered by a section.
+    ///
+    /// The range includes both end points. If either end point of the
+    /// section isn’t on the line, the section is broken and `None` is
+    /// returned. Such sections are skipped rather than guessed at.
+    pub fn range(
+        &self, section: &line::Section, store: &FullStore
+    ) -> Option<(usize, usize)> {
+        let start = self.index(section.start_point(self.line.data(), store))?;
+        let end = self.index(section.end_point(self.line.data(), store))?;
+        Some((start.min(end), start.max(end)))
+    }
+
+    /// Returns the index of a point on the line.
+    fn index(&self, point: point::Document) -> Option<usize> {
+        self.points.iter().position(|item| item.link() == point.link())
+    }
+
+    /// Returns the length in metres between the points at two indexes.
+    ///
+    /// This is the sum of the lengths of the segments in between as
+    /// determined by [`segment_length`].
+    pub fn length(
+        &self, start: usize, end: usize, state: &RequestState
+    ) -> f64 {
+        self.points[start..=end].windows(2).map(|pair| {
+            segment_length(pair[0], pair[1], self.line.link(), state)
+        }).sum()
+    }
+}
+
+
+//------------ segment_length ------------------------------------------------
+
+/// Returns the length of the segment between two points of a line.
+///
+/// The length is the difference between the locations of the points on
+/// the line. If either location is missing or can’t be parsed, the
+/// distance between the coordinates of the points is used instead. If
+/// that isn’t available either, the length is zero.
+pub fn segment_length(
+    left: point::Document,
+    right: point::Document,
+    line: line::Link,
+    state: &RequestState,
+) -> f64 {
+    let location = |point: point::Document| {
+        point.data().line_location(line).and_then(|(location, _)| {
+            parse_km(&location.to_string())
+        })
+    };
+    if let (Some(left), Some(right)) = (location(left), location(right)) {
+        return (left - right).abs() * 1000.
+    }
+    match (
+        geo::point_coord(left, state.store()),
+        geo::point_coord(right, state.store()),
+    ) {
+        (Some(left), Some(right)) => left.distance(right),
+        _ => 0.
+    }
+}
+
+/// Parses a location on a line into kilometres.
+///
+/// Both a decimal point and a decimal comma are accepted.
+fn parse_km(location: &str) -> Option<f64> {
+    location.trim().replace(',', ".").parse::<f64>().ok().filter(|km| {
+        km.is_finite()
+    })
+}
+
+
+//------------ Tests ---------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_locations() {
+        assert_eq!(parse_km("12.3"), Some(12.3));
+        assert_eq!(parse_km(" 4,25 "), Some(4.25));
+        assert_eq!(parse_km("-0.8"), Some(-0.8));
+        assert_eq!(parse_km("12.3+0.1"), None);
+        assert_eq!(parse_km(""), None);
+    }
+}
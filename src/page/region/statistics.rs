@@ -0,0 +1,628 @@
+//! Statistics about the network of a region.
+//!
+//! The length of the network over time is derived from the events of the
+//! lines in the region. The part of each line inside the region is split
+//! into segments between consecutive points whose lengths are taken from
+//! the locations of the points or, failing that, their coordinates. The
+//! events then tell us from which year a segment was open, closed, or
+//! electrified. Segments shared by several lines are only counted once.
+
+use std::collections::HashMap;
+use htmlfn::html;
+use htmlfn::html::attr;
+use htmlfn::core::Content;
+use htmlfn::utils::{display, either, iter};
+use raildata::document::entity;
+use raildata::document::line::{self, Status};
+use raildata::store::FullStore;
+use crate::{event, i18n, route};
+use crate::csv::Csv;
+use crate::page::frame;
+use crate::state::RequestState;
+use crate::svg::Svg;
+use super::components::Chapter;
+use super::section::LinePoints;
+
+const CHAPTER: Chapter = Chapter::Statistics;
+
+
+pub fn page<'a>(
+    entity: entity::Document<'a>, state: &'a RequestState
+) -> impl frame::Page + 'a {
+    let years = years(entity, state);
+    frame::standard(state, CHAPTER.title(entity, state), (), (), (
+        CHAPTER.headline(entity, state),
+        either(years.is_empty(),
+            || html::p(i18n::term::region::statistics::empty(state)),
+            move || (
+                html::h2(i18n::term::region::statistics::length(state)),
+                html::p::class("region-statistics-chart",
+                    html::img::attrs((
+                        attr::src(
+                            route::region::StatisticsSvg::href(entity, state)
+                        ),
+                        attr::alt(i18n::term::region::statistics::alt(state)),
+                    ))
+                ),
+                html::ul::class("region-statistics-downloads",
+                    html::li(html::a(
+                        route::region::StatisticsCsv::href(entity, state),
+                        i18n::term::region::statistics::csv(state)
+                    ))
+                ),
+                table(years, state),
+            )
+        )
+    ))
+}
+
+/// The table of the years in which the network changed.
+fn table<'a>(years: Vec<Year>, state: &'a RequestState) -> impl Content + 'a {
+    let changed: Vec<_> = years.iter().enumerate().filter(|(idx, year)| {
+        match idx.checked_sub(1) {
+            Some(prev) => !years[prev].same_length(year),
+            None => true
+        }
+    }).map(|(_, year)| *year).collect();
+    html::table::class("region-statistics", (
+        html::tr((
+            html::th(i18n::term::region::statistics::year(state)),
+            html::th(i18n::term::region::statistics::open(state)),
+            html::th(i18n::term::region::statistics::closed(state)),
+            html::th(i18n::term::region::statistics::electrified(state)),
+        )),
+        iter(changed.into_iter().map(move |year| {
+            html::tr((
+                html::td(display(year.year)),
+                html::td::class("region-statistics-length", km(year.open)),
+                html::td::class("region-statistics-length", km(year.closed)),
+                html::td::class(
+                    "region-statistics-length", km(year.electrified)
+                ),
+            ))
+        }))
+    ))
+}
+
+/// Formats a length in metres as kilometres.
+fn km(length: f64) -> String {
+    format!("{:.1}", length / 1000.)
+}
+
+
+//------------ Year ----------------------------------------------------------
+
+/// The length of the network at the end of a year in metres.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Year {
+    pub year: i32,
+
+    /// The length of open segments.
+    pub open: f64,
+
+    /// The length of segments that were open once but no longer are.
+    pub closed: f64,
+
+    /// The length of open segments that are electrified.
+    pub electrified: f64,
+}
+
+impl Year {
+    fn same_length(&self, other: &Year) -> bool {
+        self.open == other.open
+            && self.closed == other.closed
+            && self.electrified == other.electrified
+    }
+}
+
+/// Returns the length of the network for each year.
+///
+/// The list starts with the first year any segment in the region changed
+/// and ends with the last such year. Events without a date are ignored.
+pub fn years(entity: entity::Document, state: &RequestState) -> Vec<Year> {
+    years_of(&segments(entity, state))
+}
+
+/// Returns the length of the network for each year from its segments.
+fn years_of(segments: &[Segment]) -> Vec<Year> {
+    let changes = || segments.iter().flat_map(|seg| {
+        seg.lines.iter().flatten().map(|change| change.0)
+    });
+    let (first, last) = match (changes().min(), changes().max()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Vec::new()
+    };
+    (first..=last).map(|year| {
+        let mut res = Year { year, .. Default::default() };
+        for seg in segments {
+            let flags = seg.at(year);
+            if flags.open {
+                res.open += seg.length;
+                if flags.electrified {
+                    res.electrified += seg.length;
+                }
+            }
+            if flags.closed {
+                res.closed += seg.length;
+            }
+        }
+        res
+    }).collect()
+}
+
+
+//------------ Segment -------------------------------------------------------
+
+/// A segment between two consecutive points.
+///
+/// Track shared by several lines appears in each of them. Since we want
+/// the length of the network rather than the sum of the lengths of its
+/// lines, such a segment is only counted once and keeps the changes of
+/// every line using it.
+struct Segment {
+    /// The length of the segment in metres.
+    ///
+    /// This is zero if neither the line location nor the coordinates of
+    /// both points are known.
+    length: f64,
+
+    /// The changes of each line using the segment.
+    ///
+    /// For each line, this is the state of the segment after each change
+    /// sorted by year.
+    lines: Vec<Vec<(i32, Flags)>>,
+}
+
+impl Segment {
+    /// Returns the state of the segment at the end of the given year.
+    ///
+    /// If the segment is open on any of its lines, it is open and
+    /// electrified if it is electrified on any of these. Otherwise, it is
+    /// closed and electrified if it is so on any line.
+    fn at(&self, year: i32) -> Flags {
+        let states: Vec<_> = self.lines.iter().map(|changes| {
+            changes.iter().take_while(|change| {
+                change.0 <= year
+            }).last().map(|change| change.1).unwrap_or_default()
+        }).collect();
+        let open: Vec<_> = states.iter().filter(|flags| {
+            flags.open
+        }).collect();
+        if !open.is_empty() {
+            Flags {
+                open: true,
+                closed: false,
+                electrified: open.iter().any(|flags| flags.electrified),
+            }
+        }
+        else {
+            Flags {
+                open: false,
+                closed: states.iter().any(|flags| flags.closed),
+                electrified: states.iter().any(|flags| flags.electrified),
+            }
+        }
+    }
+}
+
+/// The state of a segment.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+struct Flags {
+    open: bool,
+    closed: bool,
+    electrified: bool,
+}
+
+impl Flags {
+    /// Applies a change.
+    fn apply(&mut self, change: &Change) {
+        if let Some(status) = change.status {
+            self.open = matches!(status, Status::Open | Status::Reopened);
+            self.closed = matches!(
+                status,
+                Status::Suspended | Status::Closed | Status::Released
+                    | Status::Removed
+            );
+        }
+        if let Some(electrified) = change.electrified {
+            self.electrified = electrified;
+        }
+    }
+}
+
+/// An event of a line relevant for the statistics.
+#[derive(Clone, Debug, Default)]
+struct Change {
+    /// The year of the event.
+    year: i32,
+
+    /// The ranges of point indexes of the line the event applies to.
+    ///
+    /// Both ends are included.
+    ranges: Vec<(usize, usize)>,
+
+    status: Option<Status>,
+    electrified: Option<bool>,
+}
+
+impl Change {
+    /// Returns the change made by an event if there is any.
+    ///
+    /// Events without a date and events that change neither the status
+    /// nor the electrification are skipped.
+    fn from_event(
+        event: &line::Event, points: &LinePoints, store: &FullStore
+    ) -> Option<Self> {
+        let status = event.status;
+        let electrified = event.electrified.as_ref().map(|el| {
+            el.generic().is_some()
+        });
+        if status.is_none() && electrified.is_none() {
+            return None
+        }
+        let year = i32::from(event.date.iter().next()?.year());
+        let ranges = if event.sections.is_empty() {
+            vec![(0, points.last())]
+        }
+        else {
+            event.sections.iter().filter_map(|section| {
+                points.range(section, store)
+            }).collect()
+        };
+        Some(Change { year, ranges, status, electrified })
+    }
+}
+
+/// Returns the segments of all lines in the region.
+///
+/// Segments between the same two points are merged.
+fn segments(entity: entity::Document, state: &RequestState) -> Vec<Segment> {
+    let store = state.store();
+    let mut res: Vec<Segment> = Vec::new();
+    let mut index = HashMap::new();
+    for (link, section) in entity.xrefs().line_regions.iter() {
+        let line = link.document(store);
+        let points = LinePoints::new(line, store);
+        let (start, end) = match points.range(section, store) {
+            Some(range) => range,
+            None => continue
+        };
+
+        let events: Vec<_> = event::sorted_events(
+            line.data().events.iter(), |event| &event.date
+        ).into_iter().filter_map(|event| {
+            Change::from_event(event, &points, store)
+        }).collect();
+        let changes = segment_changes(start, end, &events);
+        for (idx, changes) in (start..end).zip(changes) {
+            let left = points.points()[idx].link();
+            let right = points.points()[idx + 1].link();
+            let existing = index.get(&(left, right)).or_else(|| {
+                index.get(&(right, left))
+            }).copied();
+            match existing {
+                Some(pos) => res[pos].lines.push(changes),
+                None => {
+                    index.insert((left, right), res.len());
+                    res.push(Segment {
+                        length: points.length(idx, idx + 1, state),
+                        lines: vec![changes],
+                    });
+                }
+            }
+        }
+    }
+    res
+}
+
+/// Returns the changes of the segments between two points of a line.
+///
+/// The segments are those between the points with indexes `start` and
+/// `end`. The changes need to be sorted by date already. For each
+/// segment, the result contains the state after each change sorted by
+/// year. Changes in the same year stay in the order of the events.
+fn segment_changes(
+    start: usize, end: usize, changes: &[Change]
+) -> Vec<Vec<(i32, Flags)>> {
+    let mut res = vec![Vec::new(); end - start];
+    let mut flags = vec![Flags::default(); end - start];
+    for change in changes {
+        for &(from, to) in &change.ranges {
+            for idx in from.max(start)..to.min(end) {
+                let idx = idx - start;
+                flags[idx].apply(change);
+                res[idx].push((change.year, flags[idx]));
+            }
+        }
+    }
+    for changes in &mut res {
+        // The sort is stable, so changes in the same year stay in the
+        // order of the events.
+        changes.sort_by_key(|change| change.0);
+    }
+    res
+}
+
+
+//------------ svg -----------------------------------------------------------
+
+/// The size of the chart.
+const WIDTH: f64 = 800.;
+const HEIGHT: f64 = 400.;
+
+/// The margins around the plot area.
+const LEFT: f64 = 60.;
+const RIGHT: f64 = 20.;
+const TOP: f64 = 20.;
+const BOTTOM: f64 = 50.;
+
+const STYLE: &str = "\
+    .axis { stroke: #2e3436; stroke-width: 1; }\
+    .grid { stroke: #d3d7cf; stroke-width: 1; }\
+    .label { font: 11px sans-serif; fill: #2e3436; }\
+    .label-end { font: 11px sans-serif; fill: #2e3436; text-anchor: end; }\
+    .label-middle { \
+        font: 11px sans-serif; fill: #2e3436; text-anchor: middle; \
+    }\
+    .open { fill: none; stroke: #1d3053; stroke-width: 2; }\
+    .closed { fill: none; stroke: #a40000; stroke-width: 2; }\
+    .electrified { \
+        fill: none; stroke: #c4a000; stroke-width: 2; \
+        stroke-dasharray: 6 3; \
+    }\
+";
+
+/// Draws a chart of the length of the network over time.
+pub fn svg(entity: entity::Document, state: &RequestState) -> Svg {
+    let years = years(entity, state);
+    let mut svg = Svg::new(WIDTH, HEIGHT);
+    svg.style(STYLE);
+    let (first, last) = match (years.first(), years.last()) {
+        (Some(first), Some(last)) => (first.year, last.year + 1),
+        _ => return svg
+    };
+    let max = years.iter().map(|year| {
+        year.open.max(year.closed)
+    }).fold(0., f64::max) / 1000.;
+    let km_step = step(max, 5.);
+    let top = (max / km_step).ceil().max(1.) * km_step;
+
+    let x = |year: i32| {
+        LEFT + f64::from(year - first) / f64::from(last - first)
+            * (WIDTH - LEFT - RIGHT)
+    };
+    let y = |length: f64| {
+        HEIGHT - BOTTOM - length / 1000. / top * (HEIGHT - TOP - BOTTOM)
+    };
+
+    // Horizontal grid lines with the length in kilometres.
+    for tick in 0..=((top / km_step).round() as u32) {
+        let value = (f64::from(tick) * km_step * 10.).round() / 10.;
+        let pos = y(value * 1000.);
+        svg.line((LEFT, pos), (WIDTH - RIGHT, pos), "grid");
+        svg.text((LEFT - 6., pos + 4.), 0., "label-end", &value.to_string());
+    }
+    svg.text((LEFT - 6., TOP - 6.), 0., "label-end", "km");
+
+    // Years along the bottom.
+    let year_step = step(f64::from(last - first), 10.).max(1.) as i32;
+    let mut year = (first + year_step - 1) / year_step * year_step;
+    while year <= last {
+        let pos = x(year);
+        svg.line((pos, HEIGHT - BOTTOM), (pos, HEIGHT - BOTTOM + 5.), "axis");
+        svg.text(
+            (pos, HEIGHT - BOTTOM + 18.), 0., "label-middle",
+            &year.to_string()
+        );
+        year += year_step;
+    }
+    svg.line((LEFT, TOP), (LEFT, HEIGHT - BOTTOM), "axis");
+    svg.line(
+        (LEFT, HEIGHT - BOTTOM), (WIDTH - RIGHT, HEIGHT - BOTTOM), "axis"
+    );
+
+    // The data as steps since values are for the end of each year.
+    let series: [(&str, &str, fn(&Year) -> f64); 3] = [
+        (
+            "open",
+            i18n::term::region::statistics::open(state),
+            |year| year.open
+        ),
+        (
+            "closed",
+            i18n::term::region::statistics::closed(state),
+            |year| year.closed
+        ),
+        (
+            "electrified",
+            i18n::term::region::statistics::electrified(state),
+            |year| year.electrified
+        ),
+    ];
+    for (idx, (class, title, value)) in series.iter().enumerate() {
+        svg.polyline(
+            years.iter().flat_map(|year| {
+                let pos = y(value(year));
+                [(x(year.year), pos), (x(year.year + 1), pos)]
+            }),
+            class
+        );
+
+        // Legend
+        let pos = (LEFT + 150. * idx as f64, HEIGHT - 10.);
+        svg.line(pos, (pos.0 + 20., pos.1), class);
+        svg.text((pos.0 + 26., pos.1 + 4.), 0., "label", title);
+    }
+    svg
+}
+
+/// Returns a step size for about `count` ticks up to `max`.
+///
+/// The step is one, two, or five times a power of ten.
+fn step(max: f64, count: f64) -> f64 {
+    if max <= 0. {
+        return 1.
+    }
+    let raw = max / count;
+    let base = 10f64.powf(raw.log10().floor());
+    [1., 2., 5., 10.].into_iter().map(|factor| {
+        factor * base
+    }).find(|step| *step >= raw).unwrap_or(10. * base)
+}
+
+
+//------------ csv -----------------------------------------------------------
+
+/// Returns the length of the network for each year as a CSV document.
+///
+/// Lengths are given in kilometres.
+pub fn csv(entity: entity::Document, state: &RequestState) -> Csv {
+    let mut csv = Csv::new(&["year", "open", "closed", "electrified"]);
+    for year in years(entity, state) {
+        csv.row([
+            year.year.to_string(),
+            format!("{:.3}", year.open / 1000.),
+            format!("{:.3}", year.closed / 1000.),
+            format!("{:.3}", year.electrified / 1000.),
+        ]);
+    }
+    csv
+}
+
+
+//------------ Tests ---------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nice_step() {
+        assert_eq!(step(0., 5.), 1.);
+        assert_eq!(step(-3., 5.), 1.);
+        assert_eq!(step(5., 5.), 1.);
+        assert_eq!(step(9., 5.), 2.);
+        assert_eq!(step(20., 5.), 5.);
+        assert_eq!(step(42., 5.), 10.);
+        assert_eq!(step(1234., 5.), 500.);
+        assert_eq!(step(73., 10.), 10.);
+        assert!((step(0.3, 5.) - 0.1).abs() < 1e-12);
+    }
+
+    const OPEN: Flags = Flags {
+        open: true, closed: false, electrified: false
+    };
+    const ELECTRIFIED: Flags = Flags { electrified: true, .. OPEN };
+    const CLOSED: Flags = Flags {
+        open: false, closed: true, electrified: true
+    };
+
+    fn status(year: i32, ranges: &[(usize, usize)], status: Status) -> Change {
+        Change {
+            year, ranges: ranges.into(), status: Some(status),
+            electrified: None
+        }
+    }
+
+    fn electrified(year: i32, ranges: &[(usize, usize)]) -> Change {
+        Change {
+            year, ranges: ranges.into(), status: None,
+            electrified: Some(true)
+        }
+    }
+
+    #[test]
+    fn segment_at() {
+        let segment = Segment {
+            length: 1000.,
+            lines: vec![vec![
+                (1880, OPEN), (1920, ELECTRIFIED), (1920, OPEN),
+                (1950, ELECTRIFIED), (1990, CLOSED),
+            ]],
+        };
+        assert_eq!(segment.at(1879), Flags::default());
+        assert_eq!(segment.at(1880), OPEN);
+        assert_eq!(segment.at(1919), OPEN);
+        assert_eq!(segment.at(1920), OPEN);
+        assert_eq!(segment.at(1950), ELECTRIFIED);
+        assert_eq!(segment.at(2020), CLOSED);
+    }
+
+    #[test]
+    fn shared_segment_at() {
+        let segment = Segment {
+            length: 1000.,
+            lines: vec![
+                vec![(1880, OPEN), (1960, CLOSED)],
+                vec![(1900, ELECTRIFIED), (1980, CLOSED)],
+            ],
+        };
+        assert_eq!(segment.at(1879), Flags::default());
+        assert_eq!(segment.at(1880), OPEN);
+        assert_eq!(segment.at(1900), ELECTRIFIED);
+        assert_eq!(segment.at(1960), ELECTRIFIED);
+        assert_eq!(segment.at(1980), CLOSED);
+    }
+
+    #[test]
+    fn changes_of_sections() {
+        // The part of the line in the region goes from point 2 to 5. The
+        // whole line has eight points.
+        let changes = segment_changes(2, 5, &[
+            status(1880, &[(0, 3)], Status::Open),
+            status(1890, &[(3, 7)], Status::Open),
+            electrified(1930, &[(0, 7)]),
+            status(1970, &[(4, 6), (0, 1)], Status::Closed),
+        ]);
+        assert_eq!(changes, [
+            vec![(1880, OPEN), (1930, ELECTRIFIED)],
+            vec![(1890, OPEN), (1930, ELECTRIFIED)],
+            vec![(1890, OPEN), (1930, ELECTRIFIED), (1970, CLOSED)],
+        ]);
+    }
+
+    #[test]
+    fn changes_in_same_year() {
+        let changes = segment_changes(0, 1, &[
+            status(1900, &[(0, 1)], Status::Open),
+            status(1900, &[(0, 1)], Status::Closed),
+            status(1900, &[(0, 1)], Status::Reopened),
+        ]);
+        let closed = Flags { electrified: false, .. CLOSED };
+        assert_eq!(changes, [
+            vec![(1900, OPEN), (1900, closed), (1900, OPEN)],
+        ]);
+    }
+
+    #[test]
+    fn years_of_segments() {
+        assert!(years_of(&[]).is_empty());
+
+        // A whole line opened in 1880 and electrified in 1900, a branch
+        // opened and closed in the same year, and track shared by both.
+        let segments = [
+            Segment {
+                length: 1000.,
+                lines: vec![vec![(1880, OPEN), (1900, ELECTRIFIED)]],
+            },
+            Segment {
+                length: 500.,
+                lines: vec![
+                    vec![(1880, OPEN), (1900, ELECTRIFIED)],
+                    vec![(1890, OPEN), (1890, CLOSED)],
+                ],
+            },
+            Segment {
+                length: 200.,
+                lines: vec![vec![(1890, OPEN), (1890, CLOSED)]],
+            },
+        ];
+        let years: Vec<_> = years_of(&segments).into_iter().map(|year| {
+            (year.year, year.open, year.closed, year.electrified)
+        }).collect();
+        assert_eq!(years.len(), 21);
+        assert_eq!(years[0], (1880, 1500., 0., 0.));
+        assert_eq!(years[10], (1890, 1500., 200., 0.));
+        assert_eq!(years[20], (1900, 1500., 200., 1500.));
+    }
+}
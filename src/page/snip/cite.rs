@@ -0,0 +1,40 @@
+use htmlfn::html;
+use htmlfn::core::Content;
+use htmlfn::utils::{either, join};
+use raildata::document::Document;
+use crate::{cite, i18n};
+use crate::cite::Format;
+use crate::state::RequestState;
+
+
+/// The box offering citations of a document.
+///
+/// Shows the permalink of the document and links to the citation in all
+/// available formats. For documents other than sources, the box also
+/// names the database version the page was rendered from.
+pub fn cite<'a>(
+    doc: Document<'a>, state: &'a RequestState
+) -> impl Content + 'a {
+    let permalink = cite::permalink(doc, state);
+    let is_source = doc.try_as_source().is_some();
+    html::div::class("snip-cite", (
+        html::h2(either(is_source,
+            || i18n::term::cite::source(state),
+            || i18n::term::cite::page(state),
+        )),
+        html::dl((
+            html::dt(i18n::term::cite::permalink(state)),
+            html::dd(html::a(permalink.clone(), permalink)),
+            (!is_source).then(|| (
+                html::dt(i18n::term::cite::database(state)),
+                html::dd(cite::version(state)),
+            )),
+            html::dt(i18n::term::cite::formats(state)),
+            html::dd(
+                join(" · ", Format::ALL.into_iter().map(move |format| {
+                    html::a(format.href(doc.link(), state), format.name())
+                }))
+            ),
+        ))
+    ))
+}
@@ -0,0 +1,335 @@
+//! Rendering of dates.
+//!
+//! All dates should be rendered through this module so they look the
+//! same everywhere. The [`LocalDate`] and [`LocalRange`] types provide
+//! plain text via their `Display` implementations for use outside of HTML,
+//! e.g., in exports. The functions wrap these into HTML.
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+use htmlfn::html;
+use htmlfn::core::Content;
+use htmlfn::utils::{display, join};
+use raildata::types::date::{Date, EventDate, Precision};
+use crate::lang::Lang;
+use crate::state::RequestState;
+
+
+//------------ date ----------------------------------------------------------
+
+/// A single date in the format of the request’s language.
+///
+/// Doubtful dates get the additional class `date-doubt` so they can be
+/// styled differently.
+pub fn date<'a>(
+    date: &'a Date, state: &'a RequestState
+) -> impl Content + 'a {
+    html::span::class(
+        if date.doubt() { "date date-doubt" } else { "date" },
+        display(LocalDate::new(date, state.lang()))
+    )
+}
+
+
+//------------ event_date ----------------------------------------------------
+
+/// The date of an event.
+///
+/// Events can have more than one date if sources disagree. These are all
+/// shown separated by slashes.
+pub fn event_date<'a>(
+    date: &'a EventDate, state: &'a RequestState
+) -> impl Content + 'a {
+    join(" / ", date.iter().map(move |date| self::date(date, state)))
+}
+
+
+//------------ range ---------------------------------------------------------
+
+/// A range of dates in the format of the request’s language.
+///
+/// Either end of the range can be missing for open ranges.
+pub fn range<'a>(
+    start: Option<&'a Date>,
+    end: Option<&'a Date>,
+    state: &'a RequestState
+) -> impl Content + 'a {
+    html::span::class("date-range",
+        display(LocalRange::new(start, end, state.lang()))
+    )
+}
+
+
+//------------ LocalDate -----------------------------------------------------
+
+/// A date formatted for a certain language.
+///
+/// German dates use the traditional format with Roman numerals for the
+/// month, i.e., “1. IV. 1891.” English dates use ISO 8601. Partial dates
+/// simply leave out the day or the day and month. Imprecise dates are
+/// prefixed with a marker and doubtful dates get a trailing question mark.
+#[derive(Clone, Copy)]
+pub struct LocalDate {
+    year: i32,
+    month: Option<u8>,
+    day: Option<u8>,
+    precision: Precision,
+    doubt: bool,
+    lang: Lang,
+}
+
+impl LocalDate {
+    pub fn new(date: &Date, lang: Lang) -> Self {
+        LocalDate {
+            year: date.year().into(),
+            month: date.month(),
+            day: date.day(),
+            precision: date.precision(),
+            doubt: date.doubt(),
+            lang
+        }
+    }
+
+    /// Formats the date without precision and doubt markers.
+    fn fmt_plain(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.lang {
+            Lang::De => {
+                if let Some(day) = self.day {
+                    write!(f, "{}.\u{202f}", day)?;
+                }
+                if let Some(month) = self.month.and_then(roman) {
+                    write!(f, "{}.\u{202f}", month)?;
+                }
+                write!(f, "{}", self.year)
+            }
+            Lang::En => {
+                write!(f, "{:04}", self.year)?;
+                if let Some(month) = self.month {
+                    write!(f, "-{:02}", month)?;
+                }
+                if let Some(day) = self.day {
+                    write!(f, "-{:02}", day)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for LocalDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let prefix = match (self.lang, self.precision) {
+            (_, Precision::Exact) => "",
+            (Lang::De, Precision::Circa) => "ca. ",
+            (Lang::De, Precision::Before) => "vor ",
+            (Lang::De, Precision::After) => "nach ",
+            (Lang::En, Precision::Circa) => "c. ",
+            (Lang::En, Precision::Before) => "before ",
+            (Lang::En, Precision::After) => "after ",
+        };
+        f.write_str(prefix)?;
+        self.fmt_plain(f)?;
+        if self.doubt {
+            f.write_str("\u{202f}?")?;
+        }
+        Ok(())
+    }
+}
+
+
+//------------ LocalRange ----------------------------------------------------
+
+/// A range of dates formatted for a certain language.
+///
+/// If only the start is given, the range is rendered as “since …,” if
+/// only the end is given, it is “until ….” If neither is given, the range
+/// is rendered as a dash.
+#[derive(Clone, Copy)]
+pub struct LocalRange {
+    start: Option<LocalDate>,
+    end: Option<LocalDate>,
+    lang: Lang,
+}
+
+impl LocalRange {
+    pub fn new(
+        start: Option<&Date>, end: Option<&Date>, lang: Lang
+    ) -> Self {
+        LocalRange {
+            start: start.map(|date| LocalDate::new(date, lang)),
+            end: end.map(|date| LocalDate::new(date, lang)),
+            lang
+        }
+    }
+}
+
+impl fmt::Display for LocalRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.start, self.end) {
+            (Some(start), Some(end)) => {
+                write!(f, "{}\u{202f}–\u{2009}{}", start, end)
+            }
+            (Some(start), None) => {
+                write!(f, "{} {}",
+                    match self.lang {
+                        Lang::De => "seit",
+                        Lang::En => "since",
+                    },
+                    start
+                )
+            }
+            (None, Some(end)) => {
+                write!(f, "{} {}",
+                    match self.lang {
+                        Lang::De => "bis",
+                        Lang::En => "until",
+                    },
+                    end
+                )
+            }
+            (None, None) => f.write_str("–")
+        }
+    }
+}
+
+
+//------------ UtcTime -------------------------------------------------------
+
+/// A system time displayed as UTC date and time.
+#[derive(Clone, Copy)]
+pub struct UtcTime(pub SystemTime);
+
+impl UtcTime {
+    /// Returns the seconds since the Unix epoch.
+    fn secs(self) -> u64 {
+        self.0.duration_since(UNIX_EPOCH).map(|dur| {
+            dur.as_secs()
+        }).unwrap_or(0)
+    }
+
+    /// Returns the year, month, and day of the time.
+    pub fn date(self) -> (i64, i64, i64) {
+        let days = self.secs() / 86400;
+
+        // Convert days since the epoch into a civil date. This is Howard
+        // Hinnant’s civil_from_days algorithm.
+        let z = days as i64 + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+        (year, month, day)
+    }
+}
+
+impl fmt::Display for UtcTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (year, month, day) = self.date();
+        let secs = self.secs() % 86400;
+        write!(f, "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+            year, month, day, secs / 3600, (secs / 60) % 60, secs % 60
+        )
+    }
+}
+
+
+//------------ Helpers -------------------------------------------------------
+
+fn roman(month: u8) -> Option<&'static str> {
+    match month {
+        1 => Some("I"),
+        2 => Some("II"),
+        3 => Some("III"),
+        4 => Some("IV"),
+        5 => Some("V"),
+        6 => Some("VI"),
+        7 => Some("VII"),
+        8 => Some("VIII"),
+        9 => Some("IX"),
+        10 => Some("X"),
+        11 => Some("XI"),
+        12 => Some("XII"),
+        _ => None
+    }
+}
+
+
+//------------ Tests ---------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+    use super::*;
+
+    fn date(
+        year: i32, month: Option<u8>, day: Option<u8>, lang: Lang
+    ) -> LocalDate {
+        LocalDate {
+            year, month, day,
+            precision: Precision::Exact,
+            doubt: false,
+            lang
+        }
+    }
+
+    #[test]
+    fn local_date() {
+        assert_eq!(
+            date(1891, Some(4), Some(1), Lang::De).to_string(),
+            "1.\u{202f}IV.\u{202f}1891"
+        );
+        assert_eq!(
+            date(1891, Some(4), None, Lang::De).to_string(),
+            "IV.\u{202f}1891"
+        );
+        assert_eq!(
+            date(1891, Some(4), Some(1), Lang::En).to_string(),
+            "1891-04-01"
+        );
+        assert_eq!(date(1891, None, None, Lang::En).to_string(), "1891");
+    }
+
+    #[test]
+    fn local_date_markers() {
+        let mut item = date(1891, None, None, Lang::De);
+        item.precision = Precision::Circa;
+        assert_eq!(item.to_string(), "ca. 1891");
+        item.precision = Precision::Before;
+        item.doubt = true;
+        assert_eq!(item.to_string(), "vor 1891\u{202f}?");
+        item.lang = Lang::En;
+        item.precision = Precision::After;
+        assert_eq!(item.to_string(), "after 1891\u{202f}?");
+    }
+
+    #[test]
+    fn local_range() {
+        let start = date(1891, None, None, Lang::En);
+        let end = date(1923, Some(5), None, Lang::En);
+        let range = |start, end| LocalRange { start, end, lang: Lang::En };
+        assert_eq!(
+            range(Some(start), Some(end)).to_string(),
+            "1891\u{202f}–\u{2009}1923-05"
+        );
+        assert_eq!(range(Some(start), None).to_string(), "since 1891");
+        assert_eq!(range(None, Some(end)).to_string(), "until 1923-05");
+        assert_eq!(range(None, None).to_string(), "–");
+    }
+
+    #[test]
+    fn utc_time() {
+        assert_eq!(
+            UtcTime(UNIX_EPOCH).to_string(), "1970-01-01 00:00:00 UTC"
+        );
+        assert_eq!(
+            UtcTime(
+                UNIX_EPOCH + Duration::from_secs(951_827_696)
+            ).to_string(),
+            "2000-02-29 12:34:56 UTC"
+        );
+    }
+}
@@ -7,7 +7,7 @@ use crate::state::RequestState;
 pub fn title<'a>(
     entity: entity::Document<'a>, state: &'a RequestState
 ) -> impl Text + 'a {
-    entity.data().local_short_name(state.lang().into());
+    entity.data().local_short_name(state.lang().into())
 }
 
 pub fn link<'a>(
@@ -1,5 +1,7 @@
 
+pub mod cite;
 pub mod combined;
+pub mod date;
 pub mod entity;
 pub mod line;
 pub mod local;
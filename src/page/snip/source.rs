@@ -1,10 +1,247 @@
-use htmlfn::core::Text;
+use std::collections::HashSet;
+use htmlfn::html;
+use htmlfn::core::{Content, Text};
+use htmlfn::utils::{display, iter, join};
 use raildata::document::source;
+use crate::{event, i18n};
+use crate::event::Cited;
+use crate::page::snip;
+use crate::route::Href;
 use crate::state::RequestState;
 
+/// The short title of a source.
+///
+/// Falls back to the full title and, if there isn’t one either, the key.
 pub fn title<'a>(
     source: source::Document<'a>, _state: &'a RequestState
 ) -> impl Text + 'a {
-    source.key().as_str()
+    let data = source.data();
+    data.short_title.as_ref().or(data.title.as_ref()).map(|title| {
+        title.as_str()
+    }).unwrap_or_else(|| source.key().as_str())
 }
 
+/// The full title of a source.
+pub fn full_title<'a>(
+    source: source::Document<'a>, _state: &'a RequestState
+) -> impl Text + 'a {
+    let data = source.data();
+    data.title.as_ref().or(data.short_title.as_ref()).map(|title| {
+        title.as_str()
+    }).unwrap_or_else(|| source.key().as_str())
+}
+
+
+pub fn link<'a>(
+    source: source::Document<'a>, state: &'a RequestState
+) -> impl Content + 'a {
+    html::a(
+        source.href(state),
+        title(source, state),
+    )
+}
+
+
+//------------ Footnotes -----------------------------------------------------
+
+/// The sources cited by a document, numbered for footnote references.
+///
+/// Sources are numbered in the order they first appear in the document’s
+/// events, so the numbers are the same on all pages of the document.
+pub struct Footnotes<'a> {
+    sources: Vec<source::Document<'a>>,
+}
+
+impl<'a> Footnotes<'a> {
+    pub fn new(
+        links: impl Iterator<Item = &'a source::Link>,
+        state: &'a RequestState
+    ) -> Self {
+        let mut seen = HashSet::new();
+        Footnotes {
+            sources: links.map(|link| {
+                link.document(state.store())
+            }).filter(|source| {
+                seen.insert(source.key().as_str())
+            }).collect()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Returns the footnote number of a source.
+    fn number(
+        &self, link: &source::Link, state: &RequestState
+    ) -> Option<usize> {
+        let key = link.document(state.store()).key().as_str();
+        self.sources.iter().position(|source| {
+            source.key().as_str() == key
+        }).map(|idx| idx + 1)
+    }
+
+    /// Renders the references to the given sources.
+    ///
+    /// Renders nothing if none of the sources are known.
+    pub fn refs<'s>(
+        &self,
+        links: impl Iterator<Item = &'s source::Link>,
+        state: &RequestState
+    ) -> impl Content {
+        let mut numbers: Vec<_> = links.filter_map(|link| {
+            self.number(link, state)
+        }).collect();
+        numbers.sort_unstable();
+        numbers.dedup();
+        (!numbers.is_empty()).then(|| {
+            html::span::class("snip-source-refs",
+                join(",", numbers.into_iter().map(|number| {
+                    html::a(format!("#source-{}", number), display(number))
+                }))
+            )
+        })
+    }
+
+    /// Renders the references for the current value of a property.
+    ///
+    /// These are the sources of the latest of the events changing the
+    /// property as selected by `has_property`. Since events aren’t
+    /// necessarily in chronological order, they are sorted by date first.
+    pub fn current_refs<'s, E: Cited + 's>(
+        &self,
+        events: impl Iterator<Item = &'s E>,
+        has_property: impl Fn(&E) -> bool,
+        state: &RequestState
+    ) -> impl Content {
+        self.refs(
+            current_event(
+                events, |event| event::sort_key(event.date()),
+                has_property
+            ).into_iter().flat_map(Cited::sources),
+            state
+        )
+    }
+
+    /// Renders the numbered list of the full entries of all sources.
+    pub fn list(self, state: &'a RequestState) -> impl Content + 'a {
+        (!self.is_empty()).then(|| {
+            html::div::class("snip-source-footnotes", (
+                html::h2(i18n::term::source::citations(state)),
+                html::ol(
+                    iter(self.sources.into_iter().enumerate().map(
+                        move |(idx, source)| {
+                            html::li::id(
+                                format!("source-{}", idx + 1),
+                                entry(source, state)
+                            )
+                        }
+                    ))
+                )
+            ))
+        })
+    }
+}
+
+
+/// Returns the latest event with a property.
+///
+/// The events are ordered by `key`. Events with the same key keep their
+/// order, so the last of them wins.
+fn current_event<'s, E, K: Ord>(
+    events: impl Iterator<Item = &'s E>,
+    key: impl Fn(&E) -> K,
+    has_property: impl Fn(&E) -> bool,
+) -> Option<&'s E> {
+    let mut events: Vec<_> = events.collect();
+    events.sort_by_key(|event| key(event));
+    events.into_iter().rev().find(|event| has_property(event))
+}
+
+
+//------------ entry ---------------------------------------------------------
+
+/// The full bibliographic entry of a source.
+///
+/// The entry follows the usual form of “Author: Title. Publisher, Date,
+/// Pages.” with all parts not present in the source left out. The title
+/// links to the source’s own page.
+pub fn entry<'a>(
+    source: source::Document<'a>, state: &'a RequestState
+) -> impl Content + 'a {
+    let data = source.data();
+    let published = !data.publisher.is_empty() || data.date.is_some();
+    (
+        (!data.author.is_empty()).then(|| (
+            join(", ", data.author.iter().map(|author| {
+                snip::entity::link(author.document(state.store()), state)
+            })),
+            ": ",
+        )),
+        html::a::class("snip-source-title",
+            source.href(state), full_title(source, state)
+        ),
+        ".",
+        (!data.publisher.is_empty()).then(|| (
+            " ",
+            join(", ", data.publisher.iter().map(|publisher| {
+                snip::entity::link(publisher.document(state.store()), state)
+            })),
+        )),
+        data.date.as_ref().map(|date| (
+            (!data.publisher.is_empty()).then(|| ","),
+            " ",
+            snip::date::date(date, state),
+        )),
+        data.pages.as_ref().map(|pages| (
+            if published { ", " } else { " " },
+            display(pages),
+        )),
+        (published || data.pages.is_some()).then(|| "."),
+        data.url.as_ref().map(|url| (
+            " ",
+            html::a::class("snip-source-url", url.as_str(), url.as_str()),
+        )),
+    )
+}
+
+
+//------------ Tests ---------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Event {
+        year: i32,
+        name: Option<&'static str>,
+    }
+
+    fn current(events: &[Event]) -> Option<&'static str> {
+        current_event(
+            events.iter(), |event| event.year, |event| event.name.is_some()
+        ).and_then(|event| event.name)
+    }
+
+    #[test]
+    fn current_event_out_of_order() {
+        let events = [
+            Event { year: 1950, name: Some("new") },
+            Event { year: 1880, name: Some("old") },
+            Event { year: 1990, name: None },
+            Event { year: 1900, name: Some("middle") },
+        ];
+        assert_eq!(current(&events), Some("new"));
+    }
+
+    #[test]
+    fn current_event_same_date() {
+        let events = [
+            Event { year: 1900, name: Some("first") },
+            Event { year: 1880, name: Some("old") },
+            Event { year: 1900, name: Some("second") },
+        ];
+        assert_eq!(current(&events), Some("second"));
+        assert_eq!(current(&[Event { year: 1900, name: None }]), None);
+    }
+}
@@ -1,12 +1,16 @@
 use htmlfn::core::Text;
 use raildata::document::structure;
+use crate::event;
 use crate::state::RequestState;
 
 pub fn title<'a>(
     structure: structure::Document<'a>, state: &'a RequestState
 ) -> impl Text + 'a {
     let lang = state.lang().into();
-    for event in structure.data().events.iter().rev() {
+    let events = event::sorted_events(
+        structure.data().events.iter(), |event| &event.date
+    );
+    for event in events.into_iter().rev() {
         if let Some(name) = event.name.as_ref() {
             if let Some(name) = name.for_language(lang) {
                 return name
@@ -1,5 +1,8 @@
 use htmlfn::html;
-use raildata::document::source;
+use htmlfn::core::Content;
+use htmlfn::utils::{display, either, iter, join};
+use raildata::document::{Document, entity, line, point, source};
+use crate::i18n;
 use crate::page::{frame, snip};
 use crate::state::RequestState;
 
@@ -8,9 +11,218 @@ pub fn overview<'a>(
 ) -> impl frame::Page + 'a {
     frame::standard(state,
         snip::source::title(source, state), (), (),
-        html::h1(
-            snip::source::title(source, state)
+        (
+            html::h1(
+                snip::source::full_title(source, state)
+            ),
+            properties(source, state),
+            cited_by(source, state),
+            snip::cite::cite(Document::Source(source), state),
         )
     )
 }
 
+
+//------------ properties ----------------------------------------------------
+
+pub fn properties<'a>(
+    source: source::Document<'a>, state: &'a RequestState
+) -> impl Content + 'a {
+    let data = source.data();
+    (
+        html::h2(i18n::term::source::properties(state)),
+        html::dl::class("source-properties", (
+            // Author
+            (!data.author.is_empty()).then(|| {(
+                html::dt(i18n::term::source::property::author(state)),
+                html::dd(entities(data.author.iter(), state)),
+            )}),
+
+            // Editor
+            (!data.editor.is_empty()).then(|| {(
+                html::dt(i18n::term::source::property::editor(state)),
+                html::dd(entities(data.editor.iter(), state)),
+            )}),
+
+            // Title
+            html::dt(i18n::term::source::property::title(state)),
+            html::dd(snip::source::full_title(source, state)),
+
+            // Collection
+            data.collection.as_ref().map(|collection| {(
+                html::dt(i18n::term::source::property::collection(state)),
+                html::dd(snip::source::link(
+                    collection.document(state.store()), state
+                )),
+            )}),
+
+            // Volume
+            data.volume.as_ref().map(|volume| {(
+                html::dt(i18n::term::source::property::volume(state)),
+                html::dd(volume.as_str()),
+            )}),
+
+            // Edition
+            data.edition.as_ref().map(|edition| {(
+                html::dt(i18n::term::source::property::edition(state)),
+                html::dd(edition.as_str()),
+            )}),
+
+            // Publisher
+            (!data.publisher.is_empty()).then(|| {(
+                html::dt(i18n::term::source::property::publisher(state)),
+                html::dd(entities(data.publisher.iter(), state)),
+            )}),
+
+            // Date
+            data.date.as_ref().map(|date| {(
+                html::dt(i18n::term::source::property::date(state)),
+                html::dd(snip::date::date(date, state)),
+            )}),
+
+            // Pages
+            data.pages.as_ref().map(|pages| {(
+                html::dt(i18n::term::source::property::pages(state)),
+                html::dd(display(pages)),
+            )}),
+
+            // ISBN
+            data.isbn.as_ref().map(|isbn| {(
+                html::dt(i18n::term::source::property::isbn(state)),
+                html::dd(display(isbn)),
+            )}),
+
+            // URL
+            data.url.as_ref().map(|url| {(
+                html::dt(i18n::term::source::property::url(state)),
+                html::dd(html::a(url.as_str(), url.as_str())),
+            )}),
+
+            // Also
+            (!data.also.is_empty()).then(|| {(
+                html::dt(i18n::term::source::property::also(state)),
+                html::dd(join(", ", data.also.iter().map(|also| {
+                    snip::source::link(also.document(state.store()), state)
+                }))),
+            )}),
+        ))
+    )
+}
+
+fn entities<'a>(
+    links: impl Iterator<Item = &'a entity::Link> + 'a,
+    state: &'a RequestState
+) -> impl Content + 'a {
+    join(", ", links.map(move |link| {
+        snip::entity::link(link.document(state.store()), state)
+    }))
+}
+
+
+//------------ cited_by ------------------------------------------------------
+
+/// The documents referring to a source, grouped by type.
+pub fn cited_by<'a>(
+    source: source::Document<'a>, state: &'a RequestState
+) -> impl Content + 'a {
+    let cited = CitedBy::new(source, state);
+    let empty = cited.is_empty();
+    let CitedBy { lines, points, entities, members } = cited;
+    (
+        (!members.is_empty()).then(|| (
+            html::h2(i18n::term::source::members(state)),
+            html::ul::class("source-members",
+                iter(members.into_iter().map(move |member| {
+                    html::li(snip::source::entry(member, state))
+                }))
+            )
+        )),
+        html::h2(i18n::term::source::cited_by(state)),
+        either(empty,
+            || html::p(i18n::term::source::cited_by::empty(state)),
+            move || (
+                (!lines.is_empty()).then(|| (
+                    html::h3(i18n::term::source::cited_by::lines(state)),
+                    html::ul::class("source-cited-by",
+                        iter(lines.into_iter().map(move |line| {
+                            html::li(snip::line::link(line, state))
+                        }))
+                    )
+                )),
+                (!points.is_empty()).then(|| (
+                    html::h3(i18n::term::source::cited_by::points(state)),
+                    html::ul::class("source-cited-by",
+                        iter(points.into_iter().map(move |point| {
+                            html::li(snip::point::link(point, None, state))
+                        }))
+                    )
+                )),
+                (!entities.is_empty()).then(|| (
+                    html::h3(i18n::term::source::cited_by::entities(state)),
+                    html::ul::class("source-cited-by",
+                        iter(entities.into_iter().map(move |entity| {
+                            html::li(snip::entity::link(entity, state))
+                        }))
+                    )
+                )),
+            )
+        )
+    )
+}
+
+
+//------------ CitedBy -------------------------------------------------------
+
+/// All documents referring to a source.
+///
+/// There are no cross references for sources in the store, so these are
+/// taken from the database’s index.
+pub struct CitedBy<'a> {
+    /// The lines citing the source in their events.
+    pub lines: Vec<line::Document<'a>>,
+
+    /// The points citing the source in their events.
+    pub points: Vec<point::Document<'a>>,
+
+    /// The entities citing the source in their events.
+    pub entities: Vec<entity::Document<'a>>,
+
+    /// The sources that are part of the source as a collection.
+    pub members: Vec<source::Document<'a>>,
+}
+
+impl<'a> CitedBy<'a> {
+    pub fn new(source: source::Document<'a>, state: &'a RequestState) -> Self {
+        let store = state.store();
+        match state.index().citations(source.link()) {
+            Some(citations) => CitedBy {
+                lines: citations.lines.iter().map(|link| {
+                    link.document(store)
+                }).collect(),
+                points: citations.points.iter().map(|link| {
+                    link.document(store)
+                }).collect(),
+                entities: citations.entities.iter().map(|link| {
+                    link.document(store)
+                }).collect(),
+                members: citations.members.iter().map(|link| {
+                    link.document(store)
+                }).collect(),
+            },
+            None => CitedBy {
+                lines: Vec::new(),
+                points: Vec::new(),
+                entities: Vec::new(),
+                members: Vec::new(),
+            }
+        }
+    }
+
+    /// Returns whether no document cites the source.
+    ///
+    /// Members of a collection don’t count as citing it.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty() && self.points.is_empty()
+            && self.entities.is_empty()
+    }
+}
@@ -1,17 +1,244 @@
+use std::collections::HashSet;
 use htmlfn::html;
-use raildata::document::structure;
+use htmlfn::core::Content;
+use htmlfn::utils::{display, either, iter, join};
+use raildata::document::{Document, line, point, structure};
+use crate::{event, i18n};
+use crate::event::Cited;
 use crate::page::{frame, snip};
+use crate::page::snip::source::Footnotes;
+use crate::route::Href;
 use crate::state::RequestState;
 
 pub fn overview<'a>(
     structure: structure::Document<'a>, state: &'a RequestState
 ) -> impl frame::Page + 'a {
+    let notes = footnotes(structure, state);
     frame::standard(state,
         snip::structure::title(structure, state), (), (),
-        html::h1(
-            snip::structure::title(structure, state),
+        (
+            html::h1(
+                snip::structure::title(structure, state),
+            ),
+            properties(structure, &notes, state),
+            history(structure, &notes, state),
+            notes.list(state),
+            snip::cite::cite(Document::Structure(structure), state),
         )
     )
 }
 
 
+//------------ properties ----------------------------------------------------
+
+pub fn properties<'a>(
+    structure: structure::Document<'a>,
+    notes: &Footnotes<'a>,
+    state: &'a RequestState
+) -> impl Content + 'a {
+    let lines = lines(structure, state);
+    let points = points(structure, state);
+    (
+        html::h2(i18n::term::structure::properties(state)),
+        html::dl::class("structure-properties", (
+            // Type
+            html::dt(i18n::term::structure::property::subtype(state)),
+            html::dd(i18n::enums::structure::subtype(
+                structure.data().subtype.into_value(), state
+            )),
+
+            // Name
+            last(structure, |event| event.name.as_ref()).map(|(event, _)| {(
+                html::dt(i18n::term::structure::property::name(state)),
+                html::dd((
+                    snip::structure::title(structure, state),
+                    notes.refs(event.sources(), state),
+                )),
+            )}),
+
+            // Length
+            //
+            // The length is the only dimension the data records for
+            // structures.
+            last(structure, |event| event.length.as_ref()).map(
+                |(event, length)| {(
+                    html::dt(i18n::term::structure::property::length(state)),
+                    html::dd((
+                        display(length), "\u{202f}m",
+                        notes.refs(event.sources(), state),
+                    )),
+                )}
+            ),
+
+            // Lines
+            (!lines.is_empty()).then(|| {(
+                html::dt(i18n::term::structure::property::lines(state)),
+                html::dd(join(", ", lines.into_iter().map(move |line| {
+                    snip::line::link(line, state)
+                }))),
+            )}),
+
+            // Points
+            (!points.is_empty()).then(|| {(
+                html::dt(i18n::term::structure::property::points(state)),
+                html::dd(join(", ", points.into_iter().map(move |point| {
+                    snip::point::link(point, None, state)
+                }))),
+            )}),
+        ))
+    )
+}
+
+/// Returns the latest event setting a property and the property’s value.
+pub fn last<'a, T: 'a>(
+    structure: structure::Document<'a>,
+    op: impl Fn(&'a structure::Event) -> Option<T>,
+) -> Option<(&'a structure::Event, T)> {
+    event::sorted_events(
+        structure.data().events.iter(), |event| &event.date
+    ).into_iter().rev().find_map(|event| {
+        op(event).map(|value| (event, value))
+    })
+}
+
+
+//------------ history -------------------------------------------------------
+
+pub fn history<'a>(
+    structure: structure::Document<'a>,
+    notes: &Footnotes<'a>,
+    state: &'a RequestState
+) -> impl Content + 'a {
+    let events = event::sorted_events(
+        structure.data().events.iter(), |event| &event.date
+    );
+    let refs: Vec<_> = events.iter().map(|event| {
+        notes.refs(event.sources(), state)
+    }).collect();
+    (
+        html::h2(i18n::term::structure::history(state)),
+        either(events.is_empty(),
+            || html::p(i18n::term::structure::history::empty(state)),
+            || html::ol::class("structure-history",
+                iter(events.into_iter().zip(refs).map(
+                    move |(event, refs)| item(event, refs, state)
+                ))
+            )
+        ),
+    )
+}
+
+fn item<'a>(
+    event: &'a structure::Event,
+    refs: impl Content + 'a,
+    state: &'a RequestState
+) -> impl Content + 'a {
+    let store = state.store();
+    html::li((
+        html::p::class("structure-history-date", (
+            snip::date::event_date(&event.date, state),
+            refs,
+        )),
+        html::dl::class("structure-history-properties", (
+            // Name
+            event.name.as_ref().map(|name| {(
+                html::dt(i18n::term::structure::property::name(state)),
+                html::dd(
+                    name.for_language(state.lang().into()).unwrap_or("–")
+                ),
+            )}),
+
+            // Length
+            event.length.as_ref().map(|length| {(
+                html::dt(i18n::term::structure::property::length(state)),
+                html::dd((display(length), "\u{202f}m")),
+            )}),
+
+            // Lines
+            (!event.lines.is_empty()).then(|| {(
+                html::dt(i18n::term::structure::property::lines(state)),
+                html::dd(join(", ", event.lines.iter().map(move |line| {
+                    snip::line::link(line.document(store), state)
+                }))),
+            )}),
+
+            // Points
+            (!event.points.is_empty()).then(|| {(
+                html::dt(i18n::term::structure::property::points(state)),
+                html::dd(join(", ", event.points.iter().map(move |point| {
+                    snip::point::link(point.document(store), None, state)
+                }))),
+            )}),
+        )),
+    ))
+}
+
+
+//------------ lines, points -------------------------------------------------
+
+/// Returns all lines a structure has ever belonged to.
+pub fn lines<'a>(
+    structure: structure::Document<'a>, state: &'a RequestState
+) -> Vec<line::Document<'a>> {
+    let mut seen = HashSet::new();
+    structure.data().events.iter().flat_map(|event| {
+        event.lines.iter()
+    }).filter(|link| seen.insert(**link)).map(|link| {
+        link.document(state.store())
+    }).collect()
+}
+
+/// Returns all points a structure has ever belonged to.
+pub fn points<'a>(
+    structure: structure::Document<'a>, state: &'a RequestState
+) -> Vec<point::Document<'a>> {
+    let mut seen = HashSet::new();
+    structure.data().events.iter().flat_map(|event| {
+        event.points.iter()
+    }).filter(|link| seen.insert(**link)).map(|link| {
+        link.document(state.store())
+    }).collect()
+}
+
+/// Renders the list of structures belonging to a document.
+///
+/// Renders nothing if there are no such structures.
+pub fn structures<'a>(
+    doc: Document<'a>, state: &'a RequestState
+) -> impl Content + 'a {
+    let structures: Vec<_> = state.index().structures(
+        doc.link()
+    ).iter().map(|link| link.document(state.store())).collect();
+    (!structures.is_empty()).then(|| {(
+        html::dt(i18n::term::structure::list(state)),
+        html::dd(
+            html::ul::class("structure-list",
+                iter(structures.into_iter().map(move |structure| {
+                    html::li((
+                        html::a(
+                            structure.href(state),
+                            snip::structure::title(structure, state)
+                        ),
+                        " (",
+                        i18n::enums::structure::subtype(
+                            structure.data().subtype.into_value(), state
+                        ),
+                        ")",
+                    ))
+                }))
+            )
+        )
+    )})
+}
+
+
+//------------ Sources -------------------------------------------------------
+
+/// Returns the footnotes for all sources cited by the structure’s events.
+pub fn footnotes<'a>(
+    structure: structure::Document<'a>, state: &'a RequestState
+) -> Footnotes<'a> {
+    Footnotes::new(
+        structure.data().events.iter().flat_map(Cited::sources), state
+    )
+}
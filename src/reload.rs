@@ -0,0 +1,103 @@
+//! Reloading the database when it changes.
+
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use crate::config::Config;
+use crate::state::ServerState;
+
+
+//------------ watch ---------------------------------------------------------
+
+/// Watches the database directory and reloads the database on changes.
+///
+/// The directory is scanned every `config.reload_interval` seconds. If its
+/// [`Fingerprint`] has changed since the last scan, the database is
+/// reloaded in a blocking thread and, if successful, swapped in for new
+/// requests.
+///
+/// If the interval is zero, the function returns immediately.
+pub async fn watch(config: &Config, state: Arc<ServerState>) {
+    if config.reload_interval == 0 {
+        return
+    }
+    let path = config.database.clone();
+    let mut interval = tokio::time::interval(
+        Duration::from_secs(config.reload_interval)
+    );
+    let mut last = Fingerprint::scan(path.clone()).await;
+    loop {
+        interval.tick().await;
+        let current = Fingerprint::scan(path.clone()).await;
+        if current == last {
+            continue
+        }
+        last = current;
+
+        eprintln!("Database changed. Reloading ...");
+        let state = state.clone();
+        let config = config.clone();
+        let res = tokio::task::spawn_blocking(move || {
+            state.reload(&config)
+        }).await;
+        match res {
+            Ok(Ok(())) => eprintln!("Database reloaded."),
+            Ok(Err(_)) => {
+                eprintln!("Reloading failed. Keeping previous database.")
+            }
+            Err(err) => {
+                eprintln!("Reloading failed: {}", err)
+            }
+        }
+    }
+}
+
+
+//------------ Fingerprint ---------------------------------------------------
+
+/// A summary of the state of the database directory.
+///
+/// The fingerprint contains the path and modification time of every file
+/// and directory below the database directory. Comparing fingerprints
+/// thus notices modified files as well as files that were added, removed,
+/// or renamed, even if they keep an older modification time such as when
+/// copied with preserved times.
+///
+/// Entries starting with a dot are skipped. This keeps the `.git`
+/// directory of a database kept in a repository out of the scan.
+#[derive(Debug, Default, Eq, PartialEq)]
+struct Fingerprint {
+    entries: Vec<(PathBuf, SystemTime)>,
+}
+
+impl Fingerprint {
+    /// Scans the directory at `path`.
+    ///
+    /// Errors are treated as if there was no fingerprint. This will still
+    /// trigger a reload if the directory becomes unreadable which will then
+    /// produce a proper error report.
+    async fn scan(path: PathBuf) -> Option<Self> {
+        tokio::task::spawn_blocking(move || {
+            let mut res = Fingerprint::default();
+            res.scan_dir(&path).ok()?;
+            res.entries.sort();
+            Some(res)
+        }).await.ok().flatten()
+    }
+
+    fn scan_dir(&mut self, path: &Path) -> Result<(), io::Error> {
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue
+            }
+            let metadata = entry.metadata()?;
+            self.entries.push((entry.path(), metadata.modified()?));
+            if metadata.is_dir() {
+                self.scan_dir(&entry.path())?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+//! Routes for administrative pages.
+
+use htmlfn::core::AttributeValue;
+use httools::request::PathIter;
+use httools::response::Response;
+use crate::page;
+use crate::page::Page;
+use crate::state::RequestState;
+use super::RouteError;
+
+
+//------------ process -------------------------------------------------------
+
+pub(super) const SEGMENT: &'static str = "admin";
+
+pub(super) fn process(
+    mut path: PathIter, state: &RequestState
+) -> Result<Response, RouteError> {
+    if !state.server().admin() {
+        return Err(RouteError::NotFound)
+    }
+    let sub = match path.next() {
+        Some(sub) => sub,
+        None => return Err(RouteError::NotFound)
+    };
+    if path.next().is_some() {
+        return Err(RouteError::NotFound)
+    }
+    match sub {
+        Errors::SEGMENT => Ok(Errors::process(state)),
+        _ => Err(RouteError::NotFound)
+    }
+}
+
+
+//------------ Errors --------------------------------------------------------
+
+pub struct Errors;
+
+impl Errors {
+    const SEGMENT: &'static str = "errors";
+
+    fn process(state: &RequestState) -> Response {
+        page::admin::errors(state).ok(state)
+    }
+
+    pub fn href(state: &RequestState) -> impl AttributeValue + '_ {
+        (super::Root::href(state), SEGMENT, "/", Self::SEGMENT)
+    }
+}
@@ -0,0 +1,46 @@
+//! Routes for the JSON API.
+
+use htmlfn::core::AttributeValue;
+use httools::request::PathIter;
+use httools::response::Response;
+use crate::json;
+use crate::state::RequestState;
+use super::RouteError;
+
+
+//------------ process -------------------------------------------------------
+
+pub const SEGMENT: &'static str = "api";
+
+pub(super) fn process(
+    mut path: PathIter, state: &RequestState
+) -> Result<Response, RouteError> {
+    let sub = match path.next() {
+        Some(sub) => sub,
+        None => return Err(RouteError::NotFound)
+    };
+    if path.next().is_some() {
+        return Err(RouteError::NotFound)
+    }
+    match sub {
+        Search::SEGMENT => Ok(Search::process(state)),
+        _ => Err(RouteError::NotFound)
+    }
+}
+
+
+//------------ Search --------------------------------------------------------
+
+pub struct Search;
+
+impl Search {
+    const SEGMENT: &'static str = "search";
+
+    fn process(state: &RequestState) -> Response {
+        json::response(&json::search::search(state), state)
+    }
+
+    pub fn href(state: &RequestState) -> impl AttributeValue + '_ {
+        (super::Root::href(state), SEGMENT, "/", Self::SEGMENT)
+    }
+}
@@ -6,7 +6,7 @@ use httools::response::{ContentType, Response, ResponseBuilder};
 use crate::state::RequestState;
 use super::RouteError;
 
-pub(super) const SEGMENT: &'static str = "static";
+pub const SEGMENT: &'static str = "static";
 
 macro_rules! assets {
     ( $(
@@ -29,6 +29,21 @@ macro_rules! assets {
             }
         )*
 
+        /// Returns the path and content of all assets.
+        pub fn all() -> impl Iterator<Item = (&'static str, Vec<u8>)> {
+            [
+                $(
+                    (
+                        $path,
+                        include_bytes!(
+                            concat!("../../static/", $path)
+                        ).as_ref().into()
+                    ),
+                )*
+                ("style.css", grass::include!("style/style.scss").into()),
+            ].into_iter()
+        }
+
         pub(super) fn process(
             path: PathIter
         ) -> Result<Response, RouteError> {
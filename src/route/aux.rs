@@ -28,7 +28,7 @@ pub(super) fn process(
 pub struct Countries;
 
 impl Countries {
-    const SEGMENT: &'static str = "countries";
+    pub const SEGMENT: &'static str = "countries";
 
     fn process(state: &RequestState) -> Response {
         page::aux::countries(state).ok(state)
@@ -0,0 +1,24 @@
+//! Routes for citations of documents.
+
+use httools::response::Response;
+use raildata::document::Document;
+use crate::cite::{Citation, Format};
+use crate::state::RequestState;
+use super::RouteError;
+
+
+//------------ process -------------------------------------------------------
+
+/// Processes the subpage `sub` of a document if it is a citation.
+///
+/// Returns not found if `sub` isn’t the segment of a citation format.
+pub(super) fn process(
+    doc: Document, sub: &str, state: &RequestState
+) -> Result<Response, RouteError> {
+    match Format::from_segment(sub) {
+        Some(format) => {
+            Ok(Citation::document(doc, state).response(format, state))
+        }
+        None => Err(RouteError::NotFound)
+    }
+}
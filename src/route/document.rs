@@ -3,6 +3,7 @@ use httools::request::PathIter;
 use httools::response::Response;
 use raildata::document::Document;
 use raildata::store::DocumentLink;
+use crate::json;
 use crate::state::RequestState;
 use super::{Href, RouteError};
 
@@ -18,14 +19,55 @@ pub(super) fn process(
         Some(key) => key,
         None => return Err(RouteError::NotFound)
     };
-    let doc = match state.store().get(key) {
-        Some(doc) => doc.document(state.store()),
+
+    // A key ending in a format suffix asks for that format unless there
+    // is a document with that exact key.
+    let (link, format) = match state.store().get(key) {
+        Some(link) => (Some(link), None),
+        None => match Format::split_key(key) {
+            Some((key, format)) => (state.store().get(key), Some(format)),
+            None => (None, None),
+        }
+    };
+    let doc = match link {
+        Some(link) => link.document(state.store()),
         None => return Err(RouteError::NotFound)
     };
+    let format = match format {
+        Some(format) => {
+            if path.next().is_some() {
+                return Err(RouteError::NotFound)
+            }
+            format
+        }
+        None => {
+            if state.wants_json() && path.remaining().is_empty() {
+                Format::Json
+            }
+            else {
+                Format::Html
+            }
+        }
+    };
+    match format {
+        Format::Html => { }
+        Format::Json => {
+            return Ok(json::response(
+                &json::document::document(doc, state), state
+            ))
+        }
+        Format::GeoJson => {
+            return match json::geojson::document(doc, state) {
+                Some(value) => Ok(json::response(&value, state)),
+                None => Err(RouteError::NotFound)
+            }
+        }
+    }
     match doc {
         Document::Line(line) => super::line::process(line, path, state),
         Document::Entity(entity) => super::entity::process(entity, path, state),
         Document::Point(point) => super::point::process(point, path, state),
+        Document::Path(p) => super::path::process(p, path, state),
         Document::Source(src) => super::source::process(src, path, state),
         Document::Structure(s) => super::structure::process(s, path, state),
         _ => Err(RouteError::NotFound)
@@ -33,6 +75,48 @@ pub(super) fn process(
 }
 
 
+//------------ Format --------------------------------------------------------
+
+/// The format of a document representation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    Html,
+    Json,
+    GeoJson,
+}
+
+impl Format {
+    /// Splits a format suffix off a key.
+    fn split_key(key: &str) -> Option<(&str, Self)> {
+        if let Some(key) = key.strip_suffix(".json") {
+            Some((key, Format::Json))
+        }
+        else if let Some(key) = key.strip_suffix(".geojson") {
+            Some((key, Format::GeoJson))
+        }
+        else {
+            None
+        }
+    }
+
+    /// Returns the suffix to append to a key for this format.
+    pub fn suffix(self) -> &'static str {
+        match self {
+            Format::Html => "",
+            Format::Json => ".json",
+            Format::GeoJson => ".geojson",
+        }
+    }
+
+    /// Returns the href of a document in this format.
+    pub fn href(
+        self, link: impl Into<DocumentLink>, state: &RequestState
+    ) -> impl AttributeValue + '_ {
+        (link.into().href(state), self.suffix())
+    }
+}
+
+
 //------------ DocumentLink --------------------------------------------------
 
 impl Href for DocumentLink {
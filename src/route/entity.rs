@@ -1,7 +1,7 @@
 use htmlfn::core::AttributeValue;
 use httools::request::PathIter;
 use httools::response::Response;
-use raildata::document::entity;
+use raildata::document::{Document, entity};
 use raildata::store::DocumentLink;
 use crate::page;
 use crate::page::Page;
@@ -34,7 +34,11 @@ fn process_entity(
         return Err(RouteError::NotFound)
     }
     match sub {
-        _ => Err(RouteError::NotFound)
+        Lines::SEGMENT => Ok(Lines::process(entity, state)),
+        Points::SEGMENT => Ok(Points::process(entity, state)),
+        Lineage::SEGMENT => Ok(Lineage::process(entity, state)),
+        LineageSvg::SEGMENT => Ok(LineageSvg::process(entity, state)),
+        _ => super::cite::process(Document::Entity(entity), sub, state)
     }
 }
 
@@ -70,3 +74,79 @@ impl Overview {
     }
 }
 
+
+
+//------------ Lines ---------------------------------------------------------
+
+pub struct Lines;
+
+impl Lines {
+    pub const SEGMENT: &'static str = "lines";
+
+    fn process(entity: entity::Document, state: &RequestState) -> Response {
+        page::entity::lines(entity, state).ok(state)
+    }
+
+    pub fn href<'a>(
+        entity: entity::Document<'a>, state: &'a RequestState
+    ) -> impl AttributeValue + 'a {
+        (entity.href(state), "/", Self::SEGMENT)
+    }
+}
+
+
+//------------ Points --------------------------------------------------------
+
+pub struct Points;
+
+impl Points {
+    pub const SEGMENT: &'static str = "points";
+
+    fn process(entity: entity::Document, state: &RequestState) -> Response {
+        page::entity::points(entity, state).ok(state)
+    }
+
+    pub fn href<'a>(
+        entity: entity::Document<'a>, state: &'a RequestState
+    ) -> impl AttributeValue + 'a {
+        (entity.href(state), "/", Self::SEGMENT)
+    }
+}
+
+
+//------------ Lineage -------------------------------------------------------
+
+pub struct Lineage;
+
+impl Lineage {
+    pub const SEGMENT: &'static str = "lineage";
+
+    fn process(entity: entity::Document, state: &RequestState) -> Response {
+        page::entity::lineage(entity, state).ok(state)
+    }
+
+    pub fn href<'a>(
+        entity: entity::Document<'a>, state: &'a RequestState
+    ) -> impl AttributeValue + 'a {
+        (entity.href(state), "/", Self::SEGMENT)
+    }
+}
+
+
+//------------ LineageSvg ----------------------------------------------------
+
+pub struct LineageSvg;
+
+impl LineageSvg {
+    pub const SEGMENT: &'static str = "lineage.svg";
+
+    fn process(entity: entity::Document, state: &RequestState) -> Response {
+        page::entity::lineage::svg(entity, state).response(state)
+    }
+
+    pub fn href<'a>(
+        entity: entity::Document<'a>, state: &'a RequestState
+    ) -> impl AttributeValue + 'a {
+        (entity.href(state), "/", Self::SEGMENT)
+    }
+}
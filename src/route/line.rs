@@ -1,6 +1,7 @@
 use htmlfn::core::AttributeValue;
 use httools::request::PathIter;
 use httools::response::Response;
+use raildata::document::Document;
 use raildata::document::combined::{LineDocument, LineLink};
 use raildata::store::DocumentLink;
 use crate::page;
@@ -14,10 +15,23 @@ use super::{Href, RouteError};
 pub(super) fn process(
     line: LineDocument, mut path: PathIter, state: &RequestState
 ) -> Result<Response, RouteError> {
-    if let Some(_sub) = path.next() {
+    let sub = match path.next() {
+        Some(sub) => sub,
+        None => return Ok(Overview::process(line, state)),
+    };
+    if let Some(_subsub) = path.next() {
         return Err(RouteError::NotFound)
     }
-    Ok(page::line::overview(line, state).ok(state))
+    match sub {
+        Route::SEGMENT => Ok(Route::process(line, state)),
+        DetailedRoute::SEGMENT => Ok(DetailedRoute::process(line, state)),
+        History::SEGMENT => Ok(History::process(line, state)),
+        Sources::SEGMENT => Ok(Sources::process(line, state)),
+        Map::SEGMENT => Ok(Map::process(line, state)),
+        MapSvg::SEGMENT => Ok(MapSvg::process(line, state)),
+        DiagramSvg::SEGMENT => Ok(DiagramSvg::process(line, state)),
+        _ => super::cite::process(Document::Line(line), sub, state)
+    }
 }
 
 
@@ -38,3 +52,152 @@ impl Href for LineDocument<'_> {
     }
 }
 
+
+//------------ Overview ------------------------------------------------------
+
+pub struct Overview;
+
+impl Overview {
+    fn process(line: LineDocument, state: &RequestState) -> Response {
+        page::line::overview(line, state).ok(state)
+    }
+
+    pub fn href<'a>(
+        line: LineDocument<'a>, state: &'a RequestState
+    ) -> impl AttributeValue + 'a {
+        line.href(state)
+    }
+}
+
+
+//------------ Route ---------------------------------------------------------
+
+pub struct Route;
+
+impl Route {
+    pub const SEGMENT: &'static str = "route";
+
+    fn process(line: LineDocument, state: &RequestState) -> Response {
+        page::line::route(line, state).ok(state)
+    }
+
+    pub fn href<'a>(
+        line: LineDocument<'a>, state: &'a RequestState
+    ) -> impl AttributeValue + 'a {
+        (line.href(state), "/", Self::SEGMENT)
+    }
+}
+
+
+//------------ DetailedRoute -------------------------------------------------
+
+pub struct DetailedRoute;
+
+impl DetailedRoute {
+    pub const SEGMENT: &'static str = "detailed-route";
+
+    fn process(line: LineDocument, state: &RequestState) -> Response {
+        page::line::detailed_route(line, state).ok(state)
+    }
+
+    pub fn href<'a>(
+        line: LineDocument<'a>, state: &'a RequestState
+    ) -> impl AttributeValue + 'a {
+        (line.href(state), "/", Self::SEGMENT)
+    }
+}
+
+
+//------------ History -------------------------------------------------------
+
+pub struct History;
+
+impl History {
+    pub const SEGMENT: &'static str = "history";
+
+    fn process(line: LineDocument, state: &RequestState) -> Response {
+        page::line::history(line, state).ok(state)
+    }
+
+    pub fn href<'a>(
+        line: LineDocument<'a>, state: &'a RequestState
+    ) -> impl AttributeValue + 'a {
+        (line.href(state), "/", Self::SEGMENT)
+    }
+}
+
+
+//------------ Sources -------------------------------------------------------
+
+pub struct Sources;
+
+impl Sources {
+    pub const SEGMENT: &'static str = "sources";
+
+    fn process(line: LineDocument, state: &RequestState) -> Response {
+        page::line::sources(line, state).ok(state)
+    }
+
+    pub fn href<'a>(
+        line: LineDocument<'a>, state: &'a RequestState
+    ) -> impl AttributeValue + 'a {
+        (line.href(state), "/", Self::SEGMENT)
+    }
+}
+
+
+//------------ Map -----------------------------------------------------------
+
+pub struct Map;
+
+impl Map {
+    pub const SEGMENT: &'static str = "map";
+
+    fn process(line: LineDocument, state: &RequestState) -> Response {
+        page::line::map(line, state).ok(state)
+    }
+
+    pub fn href<'a>(
+        line: LineDocument<'a>, state: &'a RequestState
+    ) -> impl AttributeValue + 'a {
+        (line.href(state), "/", Self::SEGMENT)
+    }
+}
+
+
+//------------ MapSvg --------------------------------------------------------
+
+pub struct MapSvg;
+
+impl MapSvg {
+    pub const SEGMENT: &'static str = "map.svg";
+
+    fn process(line: LineDocument, state: &RequestState) -> Response {
+        page::line::map::svg(line, state).response(state)
+    }
+
+    pub fn href<'a>(
+        line: LineDocument<'a>, state: &'a RequestState
+    ) -> impl AttributeValue + 'a {
+        (line.href(state), "/", Self::SEGMENT)
+    }
+}
+
+
+//------------ DiagramSvg ----------------------------------------------------
+
+pub struct DiagramSvg;
+
+impl DiagramSvg {
+    pub const SEGMENT: &'static str = "diagram.svg";
+
+    fn process(line: LineDocument, state: &RequestState) -> Response {
+        page::line::diagram::svg(line, state).response(state)
+    }
+
+    pub fn href<'a>(
+        line: LineDocument<'a>, state: &'a RequestState
+    ) -> impl AttributeValue + 'a {
+        (line.href(state), "/", Self::SEGMENT)
+    }
+}
@@ -8,11 +8,15 @@ use crate::state::RequestState;
 
 //------------ Sub-modules ---------------------------------------------------
 
+pub mod admin;
+pub mod api;
 pub mod assets;
 pub mod aux;
+pub mod cite;
 pub mod document;
 pub mod entity;
 pub mod line;
+pub mod path;
 pub mod point;
 pub mod region;
 pub mod source;
@@ -34,6 +38,8 @@ impl Root {
         let mut path = path.iter();
 
         let res = match path.next() {
+            Some(admin::SEGMENT) => admin::process(path, state),
+            Some(api::SEGMENT) => api::process(path, state),
             Some(assets::SEGMENT) => assets::process(path),
             Some(document::SEGMENT) => document::process(path, state),
             Some(other) => aux::process(other, path, state),
@@ -0,0 +1,104 @@
+use htmlfn::core::AttributeValue;
+use httools::request::PathIter;
+use httools::response::Response;
+use raildata::document::path;
+use raildata::store::DocumentLink;
+use crate::page;
+use crate::page::Page;
+use crate::state::RequestState;
+use super::{Href, RouteError};
+
+
+//------------ process -------------------------------------------------------
+
+pub(super) fn process(
+    path: path::Document, mut iter: PathIter, state: &RequestState
+) -> Result<Response, RouteError> {
+    let sub = match iter.next() {
+        Some(sub) => sub,
+        None => return Ok(Overview::process(path, state)),
+    };
+    if let Some(_subsub) = iter.next() {
+        return Err(RouteError::NotFound)
+    }
+    match sub {
+        OutlineSvg::SEGMENT => Ok(OutlineSvg::process(path, state)),
+        Gpx::SEGMENT => Ok(Gpx::process(path, state)),
+        _ => Err(RouteError::NotFound)
+    }
+}
+
+
+//------------ Link ----------------------------------------------------------
+
+impl Href for path::Link {
+    fn href(self, state: &RequestState) -> impl AttributeValue + '_ {
+        DocumentLink::from(self).href(state)
+    }
+}
+
+
+//------------ Document ------------------------------------------------------
+
+impl<'a> Href for path::Document<'a> {
+    fn href(self, state: &RequestState) -> impl AttributeValue + '_ {
+        self.link().href(state)
+    }
+}
+
+
+//------------ Overview ------------------------------------------------------
+
+pub struct Overview;
+
+impl Overview {
+    fn process(path: path::Document, state: &RequestState) -> Response {
+        page::path::overview(path, state).ok(state)
+    }
+
+    pub fn href<'a>(
+        path: path::Document<'a>, state: &'a RequestState
+    ) -> impl AttributeValue + 'a {
+        path.href(state)
+    }
+}
+
+
+//------------ OutlineSvg ----------------------------------------------------
+
+pub struct OutlineSvg;
+
+impl OutlineSvg {
+    pub const SEGMENT: &'static str = "outline.svg";
+
+    fn process(path: path::Document, state: &RequestState) -> Response {
+        page::path::svg(path, state).response(state)
+    }
+
+    pub fn href<'a>(
+        path: path::Document<'a>, state: &'a RequestState
+    ) -> impl AttributeValue + 'a {
+        (path.href(state), "/", Self::SEGMENT)
+    }
+}
+
+
+//------------ Gpx -----------------------------------------------------------
+
+pub struct Gpx;
+
+impl Gpx {
+    pub const SEGMENT: &'static str = "path.gpx";
+
+    fn process(path: path::Document, state: &RequestState) -> Response {
+        page::path::gpx(path, state).response(
+            &format!("{}.gpx", path.key().as_str()), state
+        )
+    }
+
+    pub fn href<'a>(
+        path: path::Document<'a>, state: &'a RequestState
+    ) -> impl AttributeValue + 'a {
+        (path.href(state), "/", Self::SEGMENT)
+    }
+}
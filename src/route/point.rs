@@ -1,7 +1,7 @@
 use htmlfn::core::AttributeValue;
 use httools::request::PathIter;
 use httools::response::Response;
-use raildata::document::point;
+use raildata::document::{Document, point};
 use raildata::store::DocumentLink;
 use crate::page;
 use crate::page::Page;
@@ -14,10 +14,17 @@ use super::{Href, RouteError};
 pub(super) fn process(
     point: point::Document, mut path: PathIter, state: &RequestState
 ) -> Result<Response, RouteError> {
-    if let Some(_sub) = path.next() {
+    let sub = match path.next() {
+        Some(sub) => sub,
+        None => return Ok(page::point::overview(point, state).ok(state)),
+    };
+    if let Some(_subsub) = path.next() {
         return Err(RouteError::NotFound)
     }
-    Ok(page::point::overview(point, state).ok(state))
+    match sub {
+        History::SEGMENT => Ok(History::process(point, state)),
+        _ => super::cite::process(Document::Point(point), sub, state)
+    }
 }
 
 
@@ -37,3 +44,22 @@ impl<'a> Href for point::Document<'a> {
         self.link().href(state)
     }
 }
+
+
+//------------ History -------------------------------------------------------
+
+pub struct History;
+
+impl History {
+    pub const SEGMENT: &'static str = "history";
+
+    fn process(point: point::Document, state: &RequestState) -> Response {
+        page::point::history(point, state).ok(state)
+    }
+
+    pub fn href<'a>(
+        point: point::Document<'a>, state: &'a RequestState
+    ) -> impl AttributeValue + 'a {
+        (point.href(state), "/", Self::SEGMENT)
+    }
+}
@@ -1,7 +1,7 @@
 use htmlfn::core::AttributeValue;
 use httools::request::PathIter;
 use httools::response::Response;
-use raildata::document::entity;
+use raildata::document::{Document, entity};
 use crate::page;
 use crate::page::Page;
 use crate::state::RequestState;
@@ -22,7 +22,11 @@ pub(super) fn process(
     }
     match sub {
         Lines::SEGMENT => Ok(Lines::process(entity, state)),
-        _ => Err(RouteError::NotFound)
+        Points::SEGMENT => Ok(Points::process(entity, state)),
+        Statistics::SEGMENT => Ok(Statistics::process(entity, state)),
+        StatisticsSvg::SEGMENT => Ok(StatisticsSvg::process(entity, state)),
+        StatisticsCsv::SEGMENT => Ok(StatisticsCsv::process(entity, state)),
+        _ => super::cite::process(Document::Entity(entity), sub, state)
     }
 }
 
@@ -49,7 +53,7 @@ impl Overview {
 pub struct Lines;
 
 impl Lines {
-    const SEGMENT: &'static str = "lines";
+    pub const SEGMENT: &'static str = "lines";
 
     fn process(entity: entity::Document, state: &RequestState) -> Response {
         page::region::lines(entity, state).ok(state)
@@ -62,3 +66,81 @@ impl Lines {
     }
 }
 
+
+//------------ Points --------------------------------------------------------
+
+pub struct Points;
+
+impl Points {
+    pub const SEGMENT: &'static str = "points";
+
+    fn process(entity: entity::Document, state: &RequestState) -> Response {
+        page::region::points(entity, state).ok(state)
+    }
+
+    pub fn href<'a>(
+        entity: entity::Document<'a>, state: &'a RequestState
+    ) -> impl AttributeValue + 'a {
+        (entity.href(state), "/", Self::SEGMENT)
+    }
+}
+
+
+//------------ Statistics ----------------------------------------------------
+
+pub struct Statistics;
+
+impl Statistics {
+    pub const SEGMENT: &'static str = "statistics";
+
+    fn process(entity: entity::Document, state: &RequestState) -> Response {
+        page::region::statistics(entity, state).ok(state)
+    }
+
+    pub fn href<'a>(
+        entity: entity::Document<'a>, state: &'a RequestState
+    ) -> impl AttributeValue + 'a {
+        (entity.href(state), "/", Self::SEGMENT)
+    }
+}
+
+
+//------------ StatisticsSvg -------------------------------------------------
+
+pub struct StatisticsSvg;
+
+impl StatisticsSvg {
+    pub const SEGMENT: &'static str = "statistics.svg";
+
+    fn process(entity: entity::Document, state: &RequestState) -> Response {
+        page::region::statistics::svg(entity, state).response(state)
+    }
+
+    pub fn href<'a>(
+        entity: entity::Document<'a>, state: &'a RequestState
+    ) -> impl AttributeValue + 'a {
+        (entity.href(state), "/", Self::SEGMENT)
+    }
+}
+
+
+//------------ StatisticsCsv -------------------------------------------------
+
+pub struct StatisticsCsv;
+
+impl StatisticsCsv {
+    pub const SEGMENT: &'static str = "statistics.csv";
+
+    fn process(entity: entity::Document, state: &RequestState) -> Response {
+        page::region::statistics::csv(entity, state).response(
+            &format!("{}-statistics.csv", entity.key().as_str()), state
+        )
+    }
+
+    pub fn href<'a>(
+        entity: entity::Document<'a>, state: &'a RequestState
+    ) -> impl AttributeValue + 'a {
+        (entity.href(state), "/", Self::SEGMENT)
+    }
+}
+
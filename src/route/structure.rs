@@ -1,7 +1,7 @@
 use htmlfn::core::AttributeValue;
 use httools::request::PathIter;
 use httools::response::Response;
-use raildata::document::structure;
+use raildata::document::{Document, structure};
 use raildata::store::DocumentLink;
 use crate::page;
 use crate::page::Page;
@@ -14,10 +14,16 @@ use super::{Href, RouteError};
 pub(super) fn process(
     structure: structure::Document, mut path: PathIter, state: &RequestState
 ) -> Result<Response, RouteError> {
-    if let Some(_sub) = path.next() {
+    let sub = match path.next() {
+        Some(sub) => sub,
+        None => {
+            return Ok(page::structure::overview(structure, state).ok(state))
+        }
+    };
+    if let Some(_subsub) = path.next() {
         return Err(RouteError::NotFound)
     }
-    Ok(page::structure::overview(structure, state).ok(state))
+    super::cite::process(Document::Structure(structure), sub, state)
 }
 
 
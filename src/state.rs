@@ -1,76 +1,285 @@
-use std::sync::Arc;
+use std::{fs, io};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::SystemTime;
 use headers::{Cookie, HeaderMapExt};
+use httools::hyper::header::{ACCEPT, CONTENT_DISPOSITION, VARY};
 use httools::request::{Request, RequestQuery};
-use httools::response::{Response, ResponseBuilder};
+use httools::response::{ContentType, Response, ResponseBuilder};
 use raildata::catalogue::Catalogue;
 use raildata::load::load_tree;
-use raildata::load::report::{Failed, Stage};
-use raildata::store::FullStore;
+use raildata::load::report::{Failed, Report, Stage};
+use raildata::store::{DocumentLink, FullStore};
 use crate::config::Config;
+use crate::index::Index;
 use crate::lang::Lang;
 
 //------------ ServerState ---------------------------------------------------
 
 pub struct ServerState {
-    store: FullStore,
-    catalogue: Catalogue,
+    /// The currently active database.
+    ///
+    /// This is replaced wholesale when the database is reloaded. Requests
+    /// grab a copy of the arc when they start so they see a consistent
+    /// view even if a reload happens in the meantime.
+    database: RwLock<Arc<Database>>,
+
+    /// The errors of the last load attempt.
+    ///
+    /// If the database was loaded in degraded mode, these are the errors
+    /// that caused documents to be left out. If a reload failed, these are
+    /// the errors of that reload. The field is cleared again when a load
+    /// succeeds without errors.
+    load_errors: Mutex<Option<Arc<LoadErrors>>>,
+
+    /// The errors of past load attempts, oldest first.
+    ///
+    /// This keeps at most [`ERROR_HISTORY_LEN`][Self::ERROR_HISTORY_LEN]
+    /// entries.
+    error_history: Mutex<VecDeque<Arc<LoadErrors>>>,
+
     url_base: String,
+
+    /// Whether the administrative pages are enabled.
+    admin: bool,
 }
 
 impl ServerState {
+    /// The maximum number of entries in the error history.
+    const ERROR_HISTORY_LEN: usize = 20;
+
+    /// Loads the initial server state.
+    ///
+    /// If the database cannot be loaded completely, falls back to degraded
+    /// mode and leaves out all documents that cause errors. Only if that
+    /// fails, too, the function fails.
     pub fn load(config: &Config) -> Result<Self, Failed> {
-        let store = match load_tree(&config.database) {
-            Ok(store) => store,
-            Err(mut err) => {
-                err.sort();
-
-                if err.has_stage(Stage::Parse) {
-                    println!("{} errors.", err.stage_count(Stage::Parse));
-                    for item in err.iter() {
-                        if item.stage() == Stage::Parse {
-                            eprintln!("{}", item)
-                        }
-                    }
-                }
-                else {
-                    eprintln!("{} errors.", err.len());
-                    for item in err.iter() {
-                        eprintln!("{}", item)
+        let (database, errors) = match Self::load_database(config) {
+            Ok(res) => res,
+            Err(_) => return Err(Failed)
+        };
+        let res = Self {
+            database: RwLock::new(Arc::new(database)),
+            load_errors: Mutex::new(None),
+            error_history: Mutex::new(VecDeque::new()),
+            url_base: config.url_base.clone(),
+            admin: config.admin,
+        };
+        if let Some(errors) = errors {
+            res.set_load_errors(errors);
+        }
+        Ok(res)
+    }
+
+    /// Reloads the database.
+    ///
+    /// This falls back to degraded mode the same way as
+    /// [`load`][Self::load]. If loading succeeds at least in degraded
+    /// mode, the new database replaces the current one. If it fails
+    /// completely, the current database is kept. In both cases, errors are
+    /// stored for later inspection via [`load_errors`][Self::load_errors].
+    ///
+    /// Loading is a lengthy, blocking operation, so this should only be
+    /// called from a blocking thread.
+    pub fn reload(&self, config: &Config) -> Result<(), Failed> {
+        match Self::load_database(config) {
+            Ok((database, errors)) => {
+                *self.database.write().expect("poisoned lock") =
+                    Arc::new(database);
+                match errors {
+                    Some(errors) => self.set_load_errors(errors),
+                    None => {
+                        *self.load_errors.lock().expect("poisoned lock") =
+                            None;
                     }
                 }
-                return Err(Failed)
+                Ok(())
             }
-        };
-        let store = match store.into_full_store() {
-            Ok(store) => store,
-            Err(mut err) => {
-                err.sort();
-                eprintln!("{} errors.", err.len());
-                for item in err.iter() {
-                    eprintln!("{}", item)
-                }
-                return Err(Failed)
+            Err(err) => {
+                self.set_load_errors(err);
+                Err(Failed)
             }
+        }
+    }
+
+    /// Loads the database falling back to degraded mode if necessary.
+    ///
+    /// Returns the database and, if it was loaded in degraded mode, the
+    /// errors of the complete load. If loading fails even in degraded
+    /// mode, returns these errors.
+    fn load_database(
+        config: &Config
+    ) -> Result<(Database, Option<LoadErrors>), LoadErrors> {
+        let err = match Database::load(&config.database) {
+            Ok(database) => return Ok((database, None)),
+            Err(err) => err
         };
-        let catalogue = match Catalogue::generate(&store) {
-            Ok(catalogue) => catalogue,
-            Err(mut err) => {
-                err.sort();
-                eprintln!("{} errors.", err.len());
-                for item in err.iter() {
-                    eprintln!("{}", item)
-                }
-                return Err(Failed)
+        err.print();
+        eprintln!("Trying to load the database in degraded mode ...");
+        match Database::load_degraded(config, &err) {
+            Ok(database) => {
+                eprintln!(
+                    "Database loaded in degraded mode. \
+                     {} files were left out.",
+                    database.excluded().len()
+                );
+                Ok((database, Some(err)))
             }
-        };
+            Err(_) => {
+                eprintln!("Loading in degraded mode failed, too.");
+                Err(err)
+            }
+        }
+    }
 
-        Ok(Self { store, catalogue, url_base: config.url_base.clone() })
+    /// Sets the current load errors and adds them to the history.
+    fn set_load_errors(&self, errors: LoadErrors) {
+        let errors = Arc::new(errors);
+        let mut history = self.error_history.lock().expect("poisoned lock");
+        while history.len() >= Self::ERROR_HISTORY_LEN {
+            history.pop_front();
+        }
+        history.push_back(errors.clone());
+        *self.load_errors.lock().expect("poisoned lock") = Some(errors);
     }
 
     pub fn into_arc(self) -> Arc<Self> {
         Arc::new(self)
     }
 
+    /// Returns the currently active database.
+    pub fn database(&self) -> Arc<Database> {
+        self.database.read().expect("poisoned lock").clone()
+    }
+
+    /// Returns the errors of the last load if there are any.
+    pub fn load_errors(&self) -> Option<Arc<LoadErrors>> {
+        self.load_errors.lock().expect("poisoned lock").clone()
+    }
+
+    /// Returns the errors of past load attempts, newest first.
+    pub fn error_history(&self) -> Vec<Arc<LoadErrors>> {
+        self.error_history.lock().expect(
+            "poisoned lock"
+        ).iter().rev().cloned().collect()
+    }
+
+    pub fn url_base(&self) -> &str {
+        &self.url_base
+    }
+
+    /// Returns whether the administrative pages are enabled.
+    pub fn admin(&self) -> bool {
+        self.admin
+    }
+}
+
+
+//------------ Database ------------------------------------------------------
+
+/// A loaded database.
+pub struct Database {
+    store: FullStore,
+    catalogue: Catalogue,
+    index: Index,
+
+    /// The files left out when loading in degraded mode.
+    ///
+    /// The paths are relative to the database directory. If the database
+    /// was loaded completely, this is empty.
+    excluded: BTreeSet<PathBuf>,
+
+    /// The documents by the path of the file they were loaded from.
+    ///
+    /// The paths are relative to the directory the database was loaded
+    /// from.
+    files: HashMap<PathBuf, DocumentLink>,
+
+    /// The commit ID of the database if it is kept in a git repository.
+    commit: Option<String>,
+
+    /// The time the database was loaded.
+    loaded: SystemTime,
+}
+
+impl Database {
+    /// The maximum number of attempts when loading in degraded mode.
+    const DEGRADED_ATTEMPTS: usize = 8;
+
+    /// Loads the complete database from the directory at `path`.
+    pub fn load(path: &Path) -> Result<Self, LoadErrors> {
+        let errors = |report| LoadErrors::new(report, path);
+        let store = load_tree(path).map_err(errors)?;
+        let store = store.into_full_store().map_err(errors)?;
+        let catalogue = Catalogue::generate(&store).map_err(errors)?;
+        let index = Index::generate(&store);
+        let files = store.links().filter_map(|link| {
+            let file = link.document(&store).origin().path()?;
+            Some((file.strip_prefix(path).ok()?.into(), link))
+        }).collect();
+        Ok(Self {
+            store, catalogue, index, files,
+            excluded: BTreeSet::new(),
+            commit: git_head(path),
+            loaded: SystemTime::now(),
+        })
+    }
+
+    /// Loads the database leaving out all files that produce errors.
+    ///
+    /// The function starts with the files referenced in `errors`. It
+    /// mirrors the database directory into the cache directory, leaving
+    /// out these files, and tries to load from there. Since leaving out
+    /// documents may break other documents referring to them, this is
+    /// repeated with the files of the new errors removed from the mirror
+    /// until loading succeeds or no new files to leave out are found.
+    pub fn load_degraded(
+        config: &Config, errors: &LoadErrors
+    ) -> Result<Self, Failed> {
+        let mirror = config.cache.join("degraded");
+        let mut excluded = errors.files(&config.database);
+        if excluded.is_empty() {
+            return Err(Failed)
+        }
+        if let Err(err) = mirror_tree(&config.database, &mirror, &excluded) {
+            eprintln!(
+                "Failed to mirror database to {}: {}",
+                mirror.display(), err
+            );
+            return Err(Failed)
+        }
+
+        for _ in 0..Self::DEGRADED_ATTEMPTS {
+            let mut errors = match Self::load(&mirror) {
+                Ok(mut database) => {
+                    database.excluded = excluded;
+                    database.commit = git_head(&config.database);
+                    return Ok(database)
+                }
+                Err(err) => {
+                    err.print();
+                    err.files(&mirror)
+                }
+            };
+            errors.retain(|path| !excluded.contains(path));
+            if errors.is_empty() {
+                break
+            }
+            for path in &errors {
+                if let Err(err) = fs::remove_file(mirror.join(path)) {
+                    eprintln!(
+                        "Failed to remove {} from the mirror: {}",
+                        path.display(), err
+                    );
+                    return Err(Failed)
+                }
+            }
+            excluded.append(&mut errors);
+        }
+        Err(Failed)
+    }
+
     pub fn store(&self) -> &FullStore {
         &self.store
     }
@@ -79,8 +288,166 @@ impl ServerState {
         &self.catalogue
     }
 
-    pub fn url_base(&self) -> &str {
-        &self.url_base
+    pub fn index(&self) -> &Index {
+        &self.index
+    }
+
+    /// Returns the document loaded from the file at `path`.
+    ///
+    /// The path needs to be relative to the database directory.
+    pub fn document_at(&self, path: &Path) -> Option<DocumentLink> {
+        self.files.get(path).copied()
+    }
+
+    /// Returns the files left out in degraded mode.
+    pub fn excluded(&self) -> &BTreeSet<PathBuf> {
+        &self.excluded
+    }
+
+    /// Returns whether the database was loaded in degraded mode.
+    pub fn is_degraded(&self) -> bool {
+        !self.excluded.is_empty()
+    }
+
+    /// Returns the commit ID of the database if it is known.
+    pub fn commit(&self) -> Option<&str> {
+        self.commit.as_deref()
+    }
+
+    /// Returns the time the database was loaded.
+    pub fn loaded(&self) -> SystemTime {
+        self.loaded
+    }
+}
+
+
+//------------ git_head ------------------------------------------------------
+
+/// Returns the commit ID of the HEAD of a git repository at `path`.
+///
+/// Returns `None` if `path` isn’t the work tree of a git repository or
+/// the commit can’t be determined. We only read the files and don’t run
+/// git itself, so this only understands plain and packed refs.
+fn git_head(path: &Path) -> Option<String> {
+    let git = path.join(".git");
+    let head = fs::read_to_string(git.join("HEAD")).ok()?;
+    let name = match head.trim().strip_prefix("ref: ") {
+        Some(name) => name,
+        None => return Some(head.trim().into())
+    };
+    if let Ok(id) = fs::read_to_string(git.join(name)) {
+        return Some(id.trim().into())
+    }
+    let packed = fs::read_to_string(git.join("packed-refs")).ok()?;
+    packed.lines().find_map(|line| {
+        let (id, line_name) = line.split_once(' ')?;
+        (line_name == name).then(|| id.into())
+    })
+}
+
+
+//------------ mirror_tree ---------------------------------------------------
+
+/// Copies the directory tree `src` to `dest` leaving out some files.
+///
+/// The files in `excluded` are given relative to `src`. Files and
+/// directories whose names start with a dot are skipped, too, since they
+/// aren’t part of the database. Anything already present at `dest` is
+/// removed first.
+fn mirror_tree(
+    src: &Path, dest: &Path, excluded: &BTreeSet<PathBuf>
+) -> Result<(), io::Error> {
+    if dest.exists() {
+        fs::remove_dir_all(dest)?;
+    }
+    mirror_dir(src, dest, Path::new(""), excluded)
+}
+
+fn mirror_dir(
+    src: &Path, dest: &Path, rel: &Path, excluded: &BTreeSet<PathBuf>
+) -> Result<(), io::Error> {
+    fs::create_dir_all(dest.join(rel))?;
+    for entry in fs::read_dir(src.join(rel))? {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue
+        }
+        let rel = rel.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            mirror_dir(src, dest, &rel, excluded)?;
+        }
+        else if !excluded.contains(&rel) {
+            fs::copy(src.join(&rel), dest.join(&rel))?;
+        }
+    }
+    Ok(())
+}
+
+
+//------------ LoadErrors ----------------------------------------------------
+
+/// The errors that happened when trying to load the database.
+pub struct LoadErrors {
+    /// The sorted error report.
+    report: Report,
+
+    /// The directory loading was attempted from.
+    ///
+    /// When loading in degraded mode, this is the mirror in the cache
+    /// directory rather than the database directory.
+    base: PathBuf,
+
+    /// The time loading was attempted.
+    time: SystemTime,
+}
+
+impl LoadErrors {
+    fn new(mut report: Report, base: &Path) -> Self {
+        report.sort();
+        LoadErrors { report, base: base.into(), time: SystemTime::now() }
+    }
+
+    pub fn report(&self) -> &Report {
+        &self.report
+    }
+
+    pub fn base(&self) -> &Path {
+        &self.base
+    }
+
+    pub fn time(&self) -> SystemTime {
+        self.time
+    }
+
+    /// Returns the files the errors refer to.
+    ///
+    /// The paths are returned relative to `base`. Errors not referring to
+    /// a file below `base` are ignored.
+    pub fn files(&self, base: &Path) -> BTreeSet<PathBuf> {
+        self.report.iter().filter_map(|item| {
+            item.origin().path()?.strip_prefix(base).ok().map(Into::into)
+        }).collect()
+    }
+
+    /// Prints the errors to stderr.
+    ///
+    /// If there are parse errors, only those are printed since all other
+    /// errors are likely follow-up errors.
+    pub fn print(&self) {
+        if self.report.has_stage(Stage::Parse) {
+            eprintln!("{} errors.", self.report.stage_count(Stage::Parse));
+            for item in self.report.iter() {
+                if item.stage() == Stage::Parse {
+                    eprintln!("{}", item)
+                }
+            }
+        }
+        else {
+            eprintln!("{} errors.", self.report.len());
+            for item in self.report.iter() {
+                eprintln!("{}", item)
+            }
+        }
     }
 }
 
@@ -89,8 +456,18 @@ impl ServerState {
 
 pub struct RequestState {
     server: Arc<ServerState>,
+    database: Arc<Database>,
     query: RequestQuery,
     lang: Lang,
+
+    /// Whether the client prefers JSON over HTML.
+    wants_json: bool,
+
+    /// The base for all URLs if it differs from the server’s.
+    url_base: Option<String>,
+
+    /// The path of the page relative to the site root if rendering detached.
+    detached_path: Option<String>,
 }
 
 impl RequestState {
@@ -99,11 +476,35 @@ impl RequestState {
     ) -> Result<Self, Response> {
         let query = request.query();
         let lang = Self::determine_lang(request, &query)?;
+        let wants_json = Self::determine_wants_json(request);
         Ok(RequestState {
-            server, query, lang
+            database: server.database(),
+            server, query, lang, wants_json,
+            url_base: None,
+            detached_path: None,
         })
     }
 
+    /// Creates a request state for rendering pages outside of a request.
+    ///
+    /// The state is for the page at `path` given as the path segments
+    /// relative to the site root. All URLs will be relative to that page.
+    pub fn detached(
+        server: Arc<ServerState>, lang: Lang, path: &[&str]
+    ) -> Self {
+        RequestState {
+            database: server.database(),
+            server, lang,
+            query: RequestQuery::default(),
+            wants_json: false,
+            url_base: Some(
+                if path.is_empty() { "./".into() }
+                else { "../".repeat(path.len()) }
+            ),
+            detached_path: Some(path.join("/")),
+        }
+    }
+
     /// Determine the language.
     ///
     /// Returns the language and whether it was changed.
@@ -128,16 +529,45 @@ impl RequestState {
         Ok(Lang::default())
     }
 
+    /// Determines whether the client prefers JSON.
+    ///
+    /// See [`prefers_json`] for the rules.
+    fn determine_wants_json(request: &Request) -> bool {
+        request.headers().get(ACCEPT).and_then(|value| {
+            value.to_str().ok()
+        }).map(prefers_json).unwrap_or(false)
+    }
+
+    pub fn server(&self) -> &ServerState {
+        &self.server
+    }
+
+    pub fn database(&self) -> &Database {
+        &self.database
+    }
+
     pub fn store(&self) -> &FullStore {
-        self.server.store()
+        self.database.store()
     }
 
     pub fn catalogue(&self) -> &Catalogue {
-        self.server.catalogue()
+        self.database.catalogue()
+    }
+
+    pub fn index(&self) -> &Index {
+        self.database.index()
     }
 
     pub fn url_base(&self) -> &str {
-        self.server.url_base()
+        match self.url_base.as_ref() {
+            Some(base) => base.as_str(),
+            None => self.server.url_base()
+        }
+    }
+
+    /// Returns the path of the page if rendering detached.
+    pub fn detached_path(&self) -> Option<&str> {
+        self.detached_path.as_deref()
     }
 
     pub fn query(&self) -> &RequestQuery {
@@ -148,8 +578,109 @@ impl RequestState {
         self.lang
     }
 
+    pub fn wants_json(&self) -> bool {
+        self.wants_json
+    }
+
+    /// Returns a response builder with the headers common to all responses.
+    ///
+    /// Since document pages are served as HTML or JSON depending on the
+    /// Accept header, all responses declare that they vary by it.
     pub fn response(&self) -> ResponseBuilder {
-        ResponseBuilder::new().set_static_cookie(self.lang.cookie())
+        ResponseBuilder::new().set_static_cookie(
+            self.lang.cookie()
+        ).header(VARY, "Accept")
     }
+
+    /// Returns a response builder for a file to be downloaded.
+    ///
+    /// The response has the given content type and suggests saving it
+    /// under `filename`. Characters that don’t belong into a file name
+    /// are replaced with underscores.
+    pub fn download(
+        &self, content_type: ContentType, filename: &str
+    ) -> ResponseBuilder {
+        let filename: String = filename.chars().map(|ch| {
+            if ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.') {
+                ch
+            }
+            else {
+                '_'
+            }
+        }).collect();
+        self.response().content_type(content_type).header(
+            CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", filename)
+        )
+    }
+}
+
+
+//------------ prefers_json --------------------------------------------------
+
+/// Returns whether an Accept header value prefers JSON over HTML.
+///
+/// Each media range gets the quality given by its `q` parameter or one if
+/// there is none. JSON and HTML each get the quality of the most specific
+/// range matching them, with `*/*` and `type/*` as fallbacks. JSON wins
+/// only if its quality is positive and higher than that of HTML, so
+/// clients that accept anything get HTML.
+fn prefers_json(accept: &str) -> bool {
+    let mut ranges = Vec::new();
+    for item in accept.split(',') {
+        let mut parts = item.split(';');
+        let media = parts.next().unwrap_or("").trim().to_ascii_lowercase();
+        if media.is_empty() {
+            continue
+        }
+        let mut quality = 1.;
+        for param in parts {
+            if let Some((key, value)) = param.split_once('=') {
+                if key.trim().eq_ignore_ascii_case("q") {
+                    quality = value.trim().parse().unwrap_or(0.);
+                }
+            }
+        }
+        ranges.push((media, quality));
+    }
+    let quality = |media: &str| {
+        let (main, _) = media.split_once('/').unwrap_or((media, ""));
+        let wildcard = format!("{}/*", main);
+        let res = [media, wildcard.as_str(), "*/*"].into_iter().find_map(
+            |target| {
+                ranges.iter().find(|(range, _)| range == target).map(|r| r.1)
+            }
+        );
+        res.unwrap_or(0.)
+    };
+    let json = quality("application/json");
+    json > 0. && json > quality("text/html")
 }
 
+
+//------------ Tests ---------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn accept_json() {
+        assert!(prefers_json("application/json"));
+        assert!(prefers_json("application/json, text/html;q=0.5"));
+        assert!(prefers_json("text/html;q=0.1, application/*"));
+        assert!(prefers_json("Application/JSON; charset=utf-8"));
+    }
+
+    #[test]
+    fn accept_html() {
+        assert!(!prefers_json(""));
+        assert!(!prefers_json("*/*"));
+        assert!(!prefers_json(
+            "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8"
+        ));
+        assert!(!prefers_json("text/html;q=0.5, application/json;q=0.5"));
+        assert!(!prefers_json("application/json;q=0, */*"));
+        assert!(!prefers_json("text/*, application/json;q=0.9"));
+    }
+}
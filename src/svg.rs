@@ -0,0 +1,213 @@
+//! Building SVG images.
+//!
+//! Our diagrams and maps are rendered on the server into simple SVG
+//! documents. Since these only need a handful of elements, we build them
+//! directly as strings rather than pulling in a dependency.
+
+use std::fmt::Write;
+use htmlfn::core::{Content, Target};
+use httools::response::{ContentType, Response};
+use crate::state::RequestState;
+
+
+//------------ Svg -----------------------------------------------------------
+
+/// An SVG document under construction.
+pub struct Svg {
+    width: f64,
+    height: f64,
+    class: &'static str,
+    content: String,
+}
+
+impl Svg {
+    /// Creates a new, empty document of the given size.
+    pub fn new(width: f64, height: f64) -> Self {
+        Svg { width, height, class: "", content: String::new() }
+    }
+
+    /// Sets the class of the root element.
+    ///
+    /// The style sheet of an inlined document applies to the whole page,
+    /// so its rules should be scoped via this class.
+    pub fn set_class(&mut self, class: &'static str) {
+        self.class = class
+    }
+
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+
+    pub fn height(&self) -> f64 {
+        self.height
+    }
+
+    /// Adds a style sheet to the document.
+    pub fn style(&mut self, css: &str) {
+        let _ = write!(self.content, "<style>{}</style>", css);
+    }
+
+    /// Adds a polyline through the given points.
+    pub fn polyline(
+        &mut self, points: impl IntoIterator<Item = (f64, f64)>, class: &str
+    ) {
+        let _ = write!(
+            self.content, "<polyline class=\"{}\" points=\"", class
+        );
+        for (x, y) in points {
+            let _ = write!(self.content, "{:.1},{:.1} ", x, y);
+        }
+        self.content.push_str("\"/>");
+    }
+
+    /// Adds a straight line.
+    pub fn line(
+        &mut self, from: (f64, f64), to: (f64, f64), class: &str
+    ) {
+        let _ = write!(self.content,
+            "<line class=\"{}\" x1=\"{:.1}\" y1=\"{:.1}\" \
+             x2=\"{:.1}\" y2=\"{:.1}\"/>",
+            class, from.0, from.1, to.0, to.1
+        );
+    }
+
+    /// Adds a circle.
+    pub fn circle(&mut self, center: (f64, f64), radius: f64, class: &str) {
+        let _ = write!(self.content,
+            "<circle class=\"{}\" cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\"/>",
+            class, center.0, center.1, radius
+        );
+    }
+
+    /// Adds a rectangle.
+    pub fn rect(
+        &mut self, pos: (f64, f64), size: (f64, f64), class: &str
+    ) {
+        let _ = write!(self.content,
+            "<rect class=\"{}\" x=\"{:.1}\" y=\"{:.1}\" \
+             width=\"{:.1}\" height=\"{:.1}\"/>",
+            class, pos.0, pos.1, size.0, size.1
+        );
+    }
+
+    /// Adds a text.
+    ///
+    /// The text is anchored at the given position with its baseline. If
+    /// `rotate` is not zero, the text is rotated around the anchor by that
+    /// many degrees.
+    pub fn text(
+        &mut self, pos: (f64, f64), rotate: f64, class: &str, text: &str
+    ) {
+        let _ = write!(self.content,
+            "<text class=\"{}\" x=\"{:.1}\" y=\"{:.1}\"",
+            class, pos.0, pos.1
+        );
+        if rotate != 0. {
+            let _ = write!(self.content,
+                " transform=\"rotate({:.1} {:.1} {:.1})\"",
+                rotate, pos.0, pos.1
+            );
+        }
+        self.content.push('>');
+        escape(text, &mut self.content);
+        self.content.push_str("</text>");
+    }
+
+    /// Starts a link to the given URL.
+    ///
+    /// All elements added until the matching call to
+    /// [`end_link`][Self::end_link] will be part of the link.
+    pub fn start_link(&mut self, href: &str) {
+        self.content.push_str("<a href=\"");
+        escape(href, &mut self.content);
+        self.content.push_str("\">");
+    }
+
+    /// Ends a link started with [`start_link`][Self::start_link].
+    pub fn end_link(&mut self) {
+        self.content.push_str("</a>");
+    }
+
+    /// Finishes the document and returns it as a string.
+    pub fn finish(self) -> String {
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n{}\n",
+            self.element()
+        )
+    }
+
+    /// Finishes the document for inclusion in an HTML page.
+    ///
+    /// Other than with an image, links in the document work like any other
+    /// link on the page.
+    pub fn inline(self) -> Inline {
+        Inline(self.element())
+    }
+
+    /// Returns the root element with all its content.
+    fn element(&self) -> String {
+        let mut res = String::from(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\""
+        );
+        if !self.class.is_empty() {
+            let _ = write!(res, " class=\"{}\"", self.class);
+        }
+        let _ = write!(res,
+            " width=\"{0:.0}\" height=\"{1:.0}\" \
+             viewBox=\"0 0 {0:.0} {1:.0}\">{2}</svg>",
+            self.width, self.height, self.content
+        );
+        res
+    }
+
+    /// Finishes the document and returns it as a response.
+    pub fn response(self, state: &RequestState) -> Response {
+        state.response().content_type(ContentType::SVG).body(self.finish())
+    }
+}
+
+
+//------------ Inline --------------------------------------------------------
+
+/// A finished document to be included in an HTML page.
+pub struct Inline(String);
+
+impl Content for Inline {
+    fn render_content(self, target: &mut Target) {
+        target.append_slice(self.0.as_bytes());
+    }
+}
+
+
+//------------ escape --------------------------------------------------------
+
+/// Appends `text` to `target` escaping it for XML.
+pub fn escape(text: &str, target: &mut String) {
+    for ch in text.chars() {
+        match ch {
+            '<' => target.push_str("&lt;"),
+            '>' => target.push_str("&gt;"),
+            '&' => target.push_str("&amp;"),
+            '"' => target.push_str("&quot;"),
+            _ => target.push(ch),
+        }
+    }
+}
+
+
+//------------ Tests ---------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn escape_text() {
+        let mut target = String::new();
+        escape("<a href=\"x\">B & C</a>", &mut target);
+        assert_eq!(target, "&lt;a href=&quot;x&quot;&gt;B &amp; C&lt;/a&gt;");
+        target.clear();
+        escape("Köln Hbf", &mut target);
+        assert_eq!(target, "Köln Hbf");
+    }
+}